@@ -1,29 +1,134 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use crate::system::{
+    collector::SystemCollector, filesystems::FilesystemMonitor, interrupts::InterruptMonitor,
+    export::default_export_dir, processes::ProcessSampler, sampler::Sampler,
+    signal::{default_policy_path, PolicySource, SignalPolicy},
+    store::{default_store_dir, MetricStore, StoreConfig},
+};
+
+const FILESYSTEM_HISTORY_CAP: usize = 120;
+const PROC_ROOT: &str = "/proc";
+
 pub struct AppState {
     current_uid: u32,
-    target_uid: u32,
+    proc_root: PathBuf,
+    collector: Arc<Mutex<SystemCollector>>,
+    sampler: Mutex<Option<Sampler>>,
+    filesystems: Mutex<FilesystemMonitor>,
+    interrupts: Mutex<InterruptMonitor>,
+    processes: Mutex<ProcessSampler>,
+    signal_policy: Mutex<SignalPolicy>,
+    signal_policy_source: PolicySource,
+    store: Arc<Mutex<Option<MetricStore>>>,
+    export_dir: Option<PathBuf>,
 }
 
 impl AppState {
     pub fn new() -> Self {
         let uid = unsafe { libc::geteuid() as u32 };
+        let (signal_policy, signal_policy_source) = default_policy_path()
+            .map_or_else(|| (SignalPolicy::default(), PolicySource::default()), |path| SignalPolicy::load(&path));
         Self {
             current_uid: uid,
-            target_uid: uid,
+            proc_root: PathBuf::from(PROC_ROOT),
+            collector: Arc::new(Mutex::new(SystemCollector::new())),
+            sampler: Mutex::new(None),
+            filesystems: Mutex::new(FilesystemMonitor::new(FILESYSTEM_HISTORY_CAP)),
+            interrupts: Mutex::new(InterruptMonitor::new()),
+            processes: Mutex::new(ProcessSampler::new(unsafe { libc::sysconf(libc::_SC_CLK_TCK) as f64 })),
+            signal_policy: Mutex::new(signal_policy),
+            signal_policy_source,
+            // Without a writable data dir the app still runs, just without
+            // history from before this launch.
+            store: Arc::new(Mutex::new(
+                default_store_dir().and_then(|dir| MetricStore::open(&dir, StoreConfig::default()).ok()),
+            )),
+            export_dir: default_export_dir(),
         }
     }
 
-    pub fn new_for_tests(current_uid: u32, target_uid: u32) -> Self {
+    pub fn new_for_tests(current_uid: u32) -> Self {
         Self {
             current_uid,
-            target_uid,
+            proc_root: PathBuf::from(PROC_ROOT),
+            collector: Arc::new(Mutex::new(SystemCollector::new_for_tests())),
+            sampler: Mutex::new(None),
+            filesystems: Mutex::new(FilesystemMonitor::new(FILESYSTEM_HISTORY_CAP)),
+            interrupts: Mutex::new(InterruptMonitor::new()),
+            processes: Mutex::new(ProcessSampler::new(100.0)),
+            signal_policy: Mutex::new(SignalPolicy::default()),
+            signal_policy_source: PolicySource::default(),
+            store: Arc::new(Mutex::new(None)),
+            export_dir: None,
         }
     }
 
+    pub fn with_store_for_tests(mut self, store: MetricStore) -> Self {
+        self.store = Arc::new(Mutex::new(Some(store)));
+        self
+    }
+
+    pub fn with_export_dir_for_tests(mut self, export_dir: &Path) -> Self {
+        self.export_dir = Some(export_dir.to_path_buf());
+        self
+    }
+
+    pub fn with_proc_root_for_tests(mut self, proc_root: &Path) -> Self {
+        self.proc_root = proc_root.to_path_buf();
+        self
+    }
+
     pub fn current_uid(&self) -> u32 {
         self.current_uid
     }
 
-    pub fn fake_target_uid(&self) -> u32 {
-        self.target_uid
+    pub fn proc_root(&self) -> &Path {
+        &self.proc_root
+    }
+
+    pub fn collector(&self) -> &Arc<Mutex<SystemCollector>> {
+        &self.collector
+    }
+
+    pub fn sampler(&self) -> &Mutex<Option<Sampler>> {
+        &self.sampler
+    }
+
+    pub fn filesystems(&self) -> &Mutex<FilesystemMonitor> {
+        &self.filesystems
+    }
+
+    pub fn interrupts(&self) -> &Mutex<InterruptMonitor> {
+        &self.interrupts
+    }
+
+    pub fn processes(&self) -> &Mutex<ProcessSampler> {
+        &self.processes
+    }
+
+    pub fn signal_policy(&self) -> &Mutex<SignalPolicy> {
+        &self.signal_policy
+    }
+
+    pub fn signal_policy_source(&self) -> &PolicySource {
+        &self.signal_policy_source
+    }
+
+    pub fn store(&self) -> &Arc<Mutex<Option<MetricStore>>> {
+        &self.store
+    }
+
+    pub fn export_dir(&self) -> Option<&Path> {
+        self.export_dir.as_deref()
+    }
+}
+
+impl Default for AppState {
+    fn default() -> Self {
+        Self::new()
     }
 }
@@ -0,0 +1,335 @@
+use std::{
+    fs::{self, File, OpenOptions},
+    io::BufWriter,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    app_state::AppState,
+    commands::history::stored_width_ms,
+    errors::ApiError,
+    system::{
+        export::Exporter,
+        models::{ExportRequest, ExportSummary, HistorySeries, Resolution},
+    },
+};
+
+enum Source<'a> {
+    Aggregate(HistorySeries),
+    Device(&'a str),
+}
+
+pub fn list_export_series(state: &AppState) -> Result<Vec<String>, ApiError> {
+    let collector = state.collector().lock().map_err(|_| ApiError::Internal)?;
+    Ok(HistorySeries::ALL
+        .iter()
+        .map(|series| series.as_str().to_string())
+        .chain(collector.device_series())
+        .collect())
+}
+
+// Created on first use, since a minimal install may have no Downloads dir.
+fn export_dir(state: &AppState) -> Result<PathBuf, ApiError> {
+    let dir = state
+        .export_dir()
+        .ok_or_else(|| ApiError::ExportDirUnavailable("neither XDG_DOWNLOAD_DIR nor HOME is set".into()))?;
+    fs::create_dir_all(dir)
+        .and_then(|()| dir.canonicalize())
+        .map_err(|error| ApiError::ExportDirUnavailable(format!("{}: {error}", dir.display())))
+}
+
+pub fn get_export_dir(state: &AppState) -> Result<String, ApiError> {
+    Ok(export_dir(state)?.to_string_lossy().to_string())
+}
+
+fn resolve<'a>(state: &AppState, name: &'a str) -> Result<Source<'a>, ApiError> {
+    if let Some(series) = HistorySeries::from_name(name) {
+        return Ok(Source::Aggregate(series));
+    }
+    let collector = state.collector().lock().map_err(|_| ApiError::Internal)?;
+    if collector.has_device_series(name) {
+        Ok(Source::Device(name))
+    } else {
+        Err(ApiError::InvalidSeries)
+    }
+}
+
+// The webview picks the file name, but the file has to land below the export
+// dir. The parent is canonicalized so `..` and symlinked directories cannot
+// point it anywhere else.
+fn export_target(state: &AppState, raw: &str) -> Result<PathBuf, ApiError> {
+    let dir = export_dir(state)?;
+    let path = Path::new(raw);
+    let (Some(parent), Some(name)) = (path.parent(), path.file_name()) else {
+        return Err(ApiError::InvalidPath);
+    };
+    if !path.is_absolute() {
+        return Err(ApiError::InvalidPath);
+    }
+    let parent = parent.canonicalize().map_err(|_| ApiError::InvalidPath)?;
+    if !parent.starts_with(&dir) {
+        return Err(ApiError::InvalidPath);
+    }
+    let path = parent.join(name);
+    // Only a regular file may be replaced, never a symlink, fifo or directory.
+    match fs::symlink_metadata(&path) {
+        Ok(meta) if !meta.file_type().is_file() => Err(ApiError::InvalidPath),
+        _ => Ok(path),
+    }
+}
+
+// A stale part file from a failed export is cleared; anything else in its
+// place is left alone and fails the export.
+fn create_part(part: &Path) -> Result<File, ApiError> {
+    if fs::symlink_metadata(part).is_ok_and(|meta| meta.file_type().is_file()) {
+        fs::remove_file(part).map_err(|_| ApiError::Internal)?;
+    }
+    OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(part)
+        .map_err(|_| ApiError::Internal)
+}
+
+fn write_series(
+    state: &AppState,
+    request: &ExportRequest,
+    sources: &[Source],
+    file: File,
+) -> Result<u64, ApiError> {
+    let mut exporter = Exporter::new(BufWriter::new(file), request.format).map_err(|_| ApiError::Internal)?;
+    let start_ms = request.start_ms.unwrap_or(i64::MIN);
+    let end_ms = request.end_ms.unwrap_or(i64::MAX);
+
+    for source in sources {
+        match *source {
+            Source::Aggregate(series) => {
+                let resolution = request.resolution.unwrap_or(Resolution::Auto);
+                // The in-memory tiers are bounded, so they are copied out and
+                // the collector is released before anything is written.
+                let memory = {
+                    let collector = state.collector().lock().map_err(|_| ApiError::Internal)?;
+                    collector
+                        .history()
+                        .query(series, request.start_ms, request.end_ms, resolution)
+                };
+
+                // The store can hold weeks of rollups, so it is streamed a day
+                // at a time and only locked while each day is read.
+                let memory_start = memory.points.first().map_or(i64::MAX, |point| point.ts_ms);
+                if start_ms < memory_start {
+                    let width_ms = stored_width_ms(memory.resolution);
+                    let end_ms = end_ms.min(memory_start.saturating_sub(1));
+                    let days = {
+                        let store = state.store().lock().map_err(|_| ApiError::Internal)?;
+                        store.as_ref().map(|store| store.days(start_ms, end_ms)).unwrap_or_default()
+                    };
+                    for day in days {
+                        let points = {
+                            let store = state.store().lock().map_err(|_| ApiError::Internal)?;
+                            store.as_ref().map_or_else(Vec::new, |store| {
+                                store.query_day(day, series, start_ms, end_ms, width_ms)
+                            })
+                        };
+                        for point in &points {
+                            exporter.write(series.as_str(), point).map_err(|_| ApiError::Internal)?;
+                        }
+                    }
+                }
+                for point in &memory.points {
+                    exporter.write(series.as_str(), point).map_err(|_| ApiError::Internal)?;
+                }
+            }
+            // Device series are live only; the points are copied out so the
+            // sampler isn't held up by a slow disk.
+            Source::Device(name) => {
+                let points = {
+                    let collector = state.collector().lock().map_err(|_| ApiError::Internal)?;
+                    // The device may have gone away since the names were
+                    // resolved; fail rather than export fewer series than asked.
+                    collector
+                        .device_points(name, start_ms, end_ms)
+                        .ok_or(ApiError::InvalidSeries)?
+                };
+                for point in &points {
+                    exporter.write(name, point).map_err(|_| ApiError::Internal)?;
+                }
+            }
+        }
+    }
+    exporter.finish().map_err(|_| ApiError::Internal)
+}
+
+pub fn export_history(state: &AppState, request: &ExportRequest) -> Result<ExportSummary, ApiError> {
+    // Every name is checked up front so a typo does not leave half a file.
+    let sources = request
+        .series
+        .iter()
+        .map(|name| resolve(state, name))
+        .collect::<Result<Vec<_>, _>>()?;
+    let path = export_target(state, &request.path)?;
+
+    // Written next to the target and renamed at the end, so a failed export
+    // never replaces an existing file with a truncated one.
+    let mut part = path.clone().into_os_string();
+    part.push(".part");
+    let part = PathBuf::from(part);
+    let file = create_part(&part)?;
+
+    // From here on the part file is ours, so it is removed on failure.
+    match write_series(state, request, &sources, file) {
+        Ok(rows) => {
+            fs::rename(&part, &path).map_err(|_| ApiError::Internal)?;
+            Ok(ExportSummary {
+                path: path.to_string_lossy().to_string(),
+                series: sources.len(),
+                rows,
+            })
+        }
+        Err(error) => {
+            let _ = fs::remove_file(&part);
+            Err(error)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::PathBuf};
+
+    use crate::{
+        app_state::AppState,
+        errors::ApiError,
+        system::{
+            models::{ExportFormat, ExportRequest, HistorySeries, Resolution, RollupPoint},
+            store::{MetricStore, StoreConfig, StoredPoint},
+        },
+        test_support::TempDir,
+    };
+
+    fn request(path: &std::path::Path, format: ExportFormat, series: &[&str]) -> ExportRequest {
+        ExportRequest {
+            path: path.to_string_lossy().to_string(),
+            format,
+            series: series.iter().map(|name| name.to_string()).collect(),
+            start_ms: Some(1_500),
+            end_ms: None,
+            resolution: Some(Resolution::Raw),
+        }
+    }
+
+    fn sampled_state(dir: &std::path::Path) -> AppState {
+        let mut store = MetricStore::open(&dir.join("store"), StoreConfig::default()).unwrap();
+        let stored = StoredPoint {
+            series: HistorySeries::Cpu,
+            width_ms: 10_000,
+            point: RollupPoint {
+                ts_ms: 1_600,
+                min: 1.0,
+                avg: 2.0,
+                max: 3.0,
+            },
+        };
+        store.append(&[stored], 1_600).unwrap();
+        let state = AppState::new_for_tests(1000)
+            .with_store_for_tests(store)
+            .with_export_dir_for_tests(dir);
+        let mut collector = state.collector().lock().unwrap();
+        let mem = "MemTotal: 1000 kB\nMemAvailable: 250 kB\nSwapTotal: 0 kB\nSwapFree: 0 kB\n";
+        let stat = |busy: u64| format!("cpu  {busy} 0 0 100 0 0 0 0 0 0\ncpu0 {busy} 0 0 100 0 0 0 0 0 0\n");
+        collector.ingest_for_tests(&stat(0), mem, "eth0: 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0\n", "", 1_000);
+        collector.ingest_for_tests(&stat(100), mem, "eth0: 4000 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0\n", "", 2_000);
+        drop(collector);
+        state
+    }
+
+    #[test]
+    fn export_history_writes_stored_aggregate_and_device_series() {
+        let dir = TempDir::new("export-write");
+        let state = sampled_state(&dir);
+        let names = super::list_export_series(&state).unwrap();
+        assert!(names.iter().any(|name| name == "core/0"));
+        assert!(names.iter().any(|name| name == "net/eth0/rx"));
+
+        let path = dir.join("out.csv");
+        let summary = super::export_history(
+            &state,
+            &request(&path, ExportFormat::Csv, &["cpu", "net/eth0/rx"]),
+        )
+        .unwrap();
+        let written = fs::read_to_string(&path).unwrap();
+
+        assert_eq!((summary.series, summary.rows), (2, 3));
+        assert_eq!(
+            written,
+            "series,ts_ms,min,avg,max\ncpu,1600,1,2,3\ncpu,2000,100,100,100\nnet/eth0/rx,2000,4000,4000,4000\n"
+        );
+    }
+
+    #[test]
+    fn export_history_rejects_unknown_series_before_writing() {
+        let dir = TempDir::new("export-bad");
+        let state = sampled_state(&dir);
+        let path = dir.join("out.jsonl");
+        let result = super::export_history(&state, &request(&path, ExportFormat::Jsonl, &["cpu", "net/eth9/rx"]));
+        let exists = path.exists();
+
+        assert!(matches!(result, Err(ApiError::InvalidSeries)));
+        assert!(!exists);
+    }
+
+    #[test]
+    fn export_history_stays_inside_the_export_dir() {
+        let dir = TempDir::new("export-path");
+        let state = sampled_state(&dir);
+        let outside = dir.parent().unwrap().join(format!("lsm-export-escape-{}.csv", std::process::id()));
+        let escape = dir.join("..").join(outside.file_name().unwrap());
+        fs::create_dir(dir.join("nested")).unwrap();
+        std::os::unix::fs::symlink(&outside, dir.join("link.csv")).unwrap();
+
+        let export = |path: PathBuf| super::export_history(&state, &request(&path, ExportFormat::Csv, &["cpu"]));
+        let results = [
+            export(PathBuf::from("relative.csv")),
+            export(outside.clone()),
+            export(escape),
+            export(dir.join("nested")),
+            export(dir.join("link.csv")),
+        ];
+        let nested = export(dir.join("nested").join("ok.csv"));
+        let leaked = outside.exists();
+
+        assert!(results.iter().all(|result| matches!(result, Err(ApiError::InvalidPath))));
+        assert!(nested.is_ok());
+        assert!(!leaked);
+    }
+
+    #[test]
+    fn export_history_leaves_foreign_part_files_alone() {
+        let dir = TempDir::new("export-part");
+        let state = sampled_state(&dir);
+        let victim = dir.join("victim.txt");
+        fs::write(&victim, "keep").unwrap();
+        std::os::unix::fs::symlink(&victim, dir.join("out.csv.part")).unwrap();
+
+        let result = super::export_history(&state, &request(&dir.join("out.csv"), ExportFormat::Csv, &["cpu"]));
+        let link = fs::symlink_metadata(dir.join("out.csv.part")).unwrap();
+
+        assert!(matches!(result, Err(ApiError::Internal)));
+        assert!(link.file_type().is_symlink());
+        assert_eq!(fs::read_to_string(&victim).unwrap(), "keep");
+    }
+
+    #[test]
+    fn export_dir_is_created_on_first_use() {
+        let dir = TempDir::new("export-create");
+        let downloads = dir.join("Downloads");
+        let state = AppState::new_for_tests(1000).with_export_dir_for_tests(&downloads);
+
+        let reported = super::get_export_dir(&state).unwrap();
+
+        assert!(downloads.is_dir());
+        assert_eq!(PathBuf::from(reported), downloads.canonicalize().unwrap());
+        let unset = super::get_export_dir(&AppState::new_for_tests(1000));
+        assert!(matches!(unset, Err(ApiError::ExportDirUnavailable(_))));
+    }
+}
@@ -0,0 +1,25 @@
+use std::fs;
+
+use crate::{
+    app_state::AppState,
+    commands::metrics::now_ms,
+    errors::ApiError,
+    system::{
+        filesystems::{parse_mountinfo, statvfs},
+        models::FilesystemUsage,
+    },
+};
+
+pub fn list_filesystems(state: &AppState, include_history: bool) -> Result<Vec<FilesystemUsage>, ApiError> {
+    let mountinfo = fs::read_to_string("/proc/self/mountinfo").map_err(|_| ApiError::Internal)?;
+    let mounts: Vec<_> = parse_mountinfo(&mountinfo)
+        .into_iter()
+        .filter_map(|mount| {
+            let capacity = statvfs(&mount.mount_point)?;
+            Some((mount, capacity))
+        })
+        .collect();
+
+    let mut monitor = state.filesystems().lock().map_err(|_| ApiError::Internal)?;
+    Ok(monitor.ingest(&mounts, now_ms(), include_history))
+}
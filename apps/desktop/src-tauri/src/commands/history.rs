@@ -0,0 +1,122 @@
+use crate::{
+    app_state::AppState,
+    errors::ApiError,
+    system::{
+        history::{RetentionConfig, ONE_MINUTE_MS, TEN_SECONDS_MS},
+        models::{HistoryRange, HistorySeries, Resolution},
+        store::merge_history,
+    },
+};
+
+// The store only keeps rollups, so raw requests fall back to 10s points there.
+pub fn stored_width_ms(resolution: Resolution) -> i64 {
+    match resolution {
+        Resolution::OneMinute => ONE_MINUTE_MS,
+        _ => TEN_SECONDS_MS,
+    }
+}
+
+pub fn query_history(
+    state: &AppState,
+    series: HistorySeries,
+    start_ms: Option<i64>,
+    end_ms: Option<i64>,
+    resolution: Resolution,
+) -> Result<HistoryRange, ApiError> {
+    let mut range = {
+        let collector = state.collector().lock().map_err(|_| ApiError::Internal)?;
+        collector.history().query(series, start_ms, end_ms, resolution)
+    };
+
+    // Anything older than the in-memory tiers comes from the on-disk store.
+    let start_ms = start_ms.unwrap_or(i64::MIN);
+    let memory_start = range.points.first().map_or(i64::MAX, |point| point.ts_ms);
+    if start_ms < memory_start {
+        let store = state.store().lock().map_err(|_| ApiError::Internal)?;
+        if let Some(store) = store.as_ref() {
+            let end_ms = end_ms.unwrap_or(i64::MAX).min(memory_start.saturating_sub(1));
+            let stored = store.query(series, start_ms, end_ms, stored_width_ms(range.resolution));
+            range.points = merge_history(stored, std::mem::take(&mut range.points));
+        }
+    }
+    Ok(range)
+}
+
+pub fn get_history_retention(state: &AppState) -> Result<RetentionConfig, ApiError> {
+    let collector = state.collector().lock().map_err(|_| ApiError::Internal)?;
+    Ok(collector.history().retention())
+}
+
+// Out-of-range fields are clamped; the caller gets back what was applied.
+pub fn set_history_retention(state: &AppState, retention: RetentionConfig) -> Result<RetentionConfig, ApiError> {
+    let mut collector = state.collector().lock().map_err(|_| ApiError::Internal)?;
+    collector.set_retention(retention);
+    Ok(collector.history().retention())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        app_state::AppState,
+        system::{
+            history::RetentionConfig,
+            models::{HistorySeries, Resolution, RollupPoint},
+            store::{MetricStore, StoreConfig, StoredPoint},
+        },
+        test_support::TempDir,
+    };
+
+    #[test]
+    fn query_history_reads_collector_tiers() {
+        let state = AppState::new_for_tests(1000);
+        {
+            let mut collector = state.collector().lock().unwrap();
+            let mem = "MemTotal: 1000 kB\nMemAvailable: 250 kB\nSwapTotal: 0 kB\nSwapFree: 0 kB\n";
+            collector.ingest_for_tests("cpu  0 0 0 100 0 0 0 0 0 0\n", mem, "", "", 1_000);
+            collector.ingest_for_tests("cpu  50 0 0 150 0 0 0 0 0 0\n", mem, "", "", 2_000);
+        }
+
+        let cpu = super::query_history(&state, HistorySeries::Cpu, None, None, Resolution::Raw).unwrap();
+        assert_eq!(cpu.points.len(), 2);
+        assert!((cpu.points[1].avg - 50.0).abs() < 0.01);
+        let ram = super::query_history(&state, HistorySeries::Ram, Some(0), None, Resolution::TenSeconds).unwrap();
+        assert_eq!(ram.points.len(), 1);
+        assert!((ram.points[0].max - 75.0).abs() < 0.01);
+
+        let retention = RetentionConfig {
+            raw_minutes: 30,
+            ..RetentionConfig::default()
+        };
+        assert_eq!(super::set_history_retention(&state, retention).unwrap(), retention);
+        assert_eq!(super::get_history_retention(&state).unwrap().raw_minutes, 30);
+    }
+
+    #[test]
+    fn query_history_merges_stored_points_before_memory() {
+        let dir = TempDir::new("history-merge");
+        let mut store = MetricStore::open(&dir, StoreConfig::default()).unwrap();
+        let stored = StoredPoint {
+            series: HistorySeries::Cpu,
+            width_ms: 10_000,
+            point: RollupPoint {
+                ts_ms: 0,
+                min: 5.0,
+                avg: 10.0,
+                max: 15.0,
+            },
+        };
+        store.append(&[stored], 0).unwrap();
+        let state = AppState::new_for_tests(1000).with_store_for_tests(store);
+        {
+            let mut collector = state.collector().lock().unwrap();
+            let mem = "MemTotal: 1000 kB\nMemAvailable: 250 kB\nSwapTotal: 0 kB\nSwapFree: 0 kB\n";
+            collector.ingest_for_tests("cpu  0 0 0 100 0 0 0 0 0 0\n", mem, "", "", 60_000);
+        }
+
+        let range = super::query_history(&state, HistorySeries::Cpu, Some(0), None, Resolution::TenSeconds).unwrap();
+
+        let stamps: Vec<i64> = range.points.iter().map(|point| point.ts_ms).collect();
+        assert_eq!(stamps, vec![0, 60_000]);
+        assert_eq!(range.points[0].max, 15.0);
+    }
+}
@@ -0,0 +1,15 @@
+use std::fs;
+
+use crate::{
+    app_state::AppState,
+    errors::ApiError,
+    system::{clock::SampleTime, models::InterruptRates},
+};
+
+pub fn get_interrupt_rates(state: &AppState) -> Result<InterruptRates, ApiError> {
+    let interrupts = fs::read_to_string("/proc/interrupts").map_err(|_| ApiError::Internal)?;
+    let softirqs = fs::read_to_string("/proc/softirqs").map_err(|_| ApiError::Internal)?;
+
+    let mut monitor = state.interrupts().lock().map_err(|_| ApiError::Internal)?;
+    Ok(monitor.ingest(&interrupts, &softirqs, SampleTime::now()))
+}
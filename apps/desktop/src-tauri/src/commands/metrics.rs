@@ -1,226 +1,176 @@
 use std::{
     fs,
-    sync::{Mutex, OnceLock},
-    time::{SystemTime, UNIX_EPOCH},
+    path::Path,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
 };
 
 use crate::{
     app_state::AppState,
     errors::ApiError,
     system::{
-        models::{MetricPoint, MetricsSnapshot},
-        procfs::{parse_cpu_totals, parse_meminfo},
+        block::read_block_devices,
+        clock::SampleTime,
+        collector::{ProcSample, SystemCollector},
+        cpufreq::read_cpufreq_raw,
+        history::TEN_SECONDS_MS,
+        interfaces::read_interface_indexes,
+        models::{HistorySeries, MetricsSnapshot, RollupPoint},
+        power::read_power_supply_uevents,
+        sampler::{normalize_interval_ms, Sampler},
+        sensors::read_sensors,
+        store::{MetricStore, StoredPoint},
     },
 };
 
-#[derive(Clone, Copy)]
-struct LastSample {
-    ts_ms: i64,
-    net_rx: u64,
-    net_tx: u64,
-    disk_read: u64,
-    disk_write: u64,
-}
+pub const METRICS_EVENT: &str = "metrics://snapshot";
+const SYS_ROOT: &str = "/sys";
 
-#[derive(Default)]
-struct History {
-    cpu: Vec<MetricPoint>,
-    ram: Vec<MetricPoint>,
-    disk_read: Vec<MetricPoint>,
-    disk_write: Vec<MetricPoint>,
-    net_rx: Vec<MetricPoint>,
-    net_tx: Vec<MetricPoint>,
+pub(crate) fn now_ms() -> u64 {
+    SampleTime::now().wall_ms
 }
 
-const HISTORY_CAP: usize = 120;
-static LAST_SAMPLE: OnceLock<Mutex<Option<LastSample>>> = OnceLock::new();
-static HISTORY: OnceLock<Mutex<History>> = OnceLock::new();
-
-fn now_ms() -> i64 {
-    SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .map(|d| d.as_millis() as i64)
-        .unwrap_or(0)
+pub fn read_proc_sample() -> Result<ProcSample, ApiError> {
+    Ok(ProcSample {
+        stat: fs::read_to_string("/proc/stat").map_err(|_| ApiError::Internal)?,
+        meminfo: fs::read_to_string("/proc/meminfo").map_err(|_| ApiError::Internal)?,
+        net_dev: fs::read_to_string("/proc/net/dev").unwrap_or_default(),
+        interface_indexes: read_interface_indexes(Path::new(SYS_ROOT)),
+        diskstats: fs::read_to_string("/proc/diskstats").unwrap_or_default(),
+        block_devices: read_block_devices(Path::new(SYS_ROOT)),
+        loadavg: fs::read_to_string("/proc/loadavg").unwrap_or_default(),
+        pressure_cpu: fs::read_to_string("/proc/pressure/cpu").ok(),
+        pressure_memory: fs::read_to_string("/proc/pressure/memory").ok(),
+        pressure_io: fs::read_to_string("/proc/pressure/io").ok(),
+        sensors: read_sensors(Path::new(SYS_ROOT)),
+        cpufreq: read_cpufreq_raw(Path::new(SYS_ROOT)),
+        power_supplies: read_power_supply_uevents(Path::new(SYS_ROOT)),
+        vmstat: fs::read_to_string("/proc/vmstat").unwrap_or_default(),
+    })
 }
 
-fn parse_network_totals(input: &str) -> (u64, u64) {
-    let mut total_rx = 0_u64;
-    let mut total_tx = 0_u64;
-
-    for line in input.lines() {
-        let trimmed = line.trim();
-        if !trimmed.contains(':') {
-            continue;
-        }
-
-        let mut parts = trimmed.split(':');
-        let iface = parts.next().unwrap_or("").trim();
-        if iface == "lo" || iface.is_empty() {
-            continue;
-        }
-
-        let data = parts.next().unwrap_or("");
-        let cols: Vec<&str> = data.split_whitespace().collect();
-        if cols.len() < 9 {
-            continue;
+// Completed 10s rollups go to disk; a failed write only costs persistence.
+// It is reported once per run of failures rather than on every sample.
+fn persist_rollups(rollups: Vec<(HistorySeries, RollupPoint)>, store: &Mutex<Option<MetricStore>>, now_ms: u64) {
+    static FAILING: AtomicBool = AtomicBool::new(false);
+    let records: Vec<StoredPoint> = rollups
+        .into_iter()
+        .map(|(series, point)| StoredPoint {
+            series,
+            width_ms: TEN_SECONDS_MS,
+            point,
+        })
+        .collect();
+    if records.is_empty() {
+        return;
+    }
+    let Ok(mut store) = store.lock() else {
+        return;
+    };
+    let Some(store) = store.as_mut() else {
+        return;
+    };
+    match store.append(&records, now_ms as i64) {
+        Ok(()) => FAILING.store(false, Ordering::Relaxed),
+        Err(error) => {
+            if !FAILING.swap(true, Ordering::Relaxed) {
+                eprintln!("history store: failed to write rollups: {error}");
+            }
         }
-
-        total_rx = total_rx.saturating_add(cols[0].parse::<u64>().unwrap_or(0));
-        total_tx = total_tx.saturating_add(cols[8].parse::<u64>().unwrap_or(0));
     }
-
-    (total_rx, total_tx)
 }
 
-fn parse_disk_totals(input: &str) -> (u64, u64) {
-    let mut total_read_bytes = 0_u64;
-    let mut total_write_bytes = 0_u64;
+fn ingest_sample(
+    collector: &Mutex<SystemCollector>,
+    store: &Arc<Mutex<Option<MetricStore>>>,
+    sample: &ProcSample,
+) -> Option<MetricsSnapshot> {
+    let time = SampleTime::now();
+    // The store syncs and may compact a whole day, so it is written only
+    // after the collector is released; a slow disk must not stall snapshots
+    // or history queries.
+    let (snapshot, rollups) = {
+        let mut collector = collector.lock().ok()?;
+        let snapshot = collector.ingest(sample, time);
+        (snapshot, collector.take_completed_rollups())
+    };
+    persist_rollups(rollups, store, time.wall_ms);
+    snapshot
+}
 
-    for line in input.lines() {
-        let cols: Vec<&str> = line.split_whitespace().collect();
-        if cols.len() < 14 {
-            continue;
-        }
+fn sample_now(state: &AppState) -> Result<(), ApiError> {
+    let sample = read_proc_sample()?;
+    ingest_sample(state.collector(), state.store(), &sample).ok_or(ApiError::Internal)?;
+    Ok(())
+}
 
-        let device = cols[2];
-        if device.starts_with("loop")
-            || device.starts_with("ram")
-            || device.starts_with("dm-")
-            || device.starts_with("sr")
-        {
-            continue;
-        }
+fn has_sample(state: &AppState) -> Result<bool, ApiError> {
+    Ok(state.collector().lock().map_err(|_| ApiError::Internal)?.latest_ts_ms().is_some())
+}
 
-        let read_sectors = cols[5].parse::<u64>().unwrap_or(0);
-        let write_sectors = cols[9].parse::<u64>().unwrap_or(0);
-        total_read_bytes = total_read_bytes.saturating_add(read_sectors.saturating_mul(512));
-        total_write_bytes = total_write_bytes.saturating_add(write_sectors.saturating_mul(512));
+// Histories only carry points newer than `since_ms`, the `ts_ms` of the last
+// snapshot the caller merged; full history comes from `bootstrap_metrics`.
+pub fn get_metrics_snapshot(state: &AppState, since_ms: Option<i64>) -> Result<MetricsSnapshot, ApiError> {
+    // While the sampler owns the cadence, polling must not feed extra samples
+    // into the collector or the rates would be skewed.
+    let sampling = state.sampler().lock().map_err(|_| ApiError::Internal)?.is_some();
+    if !sampling || !has_sample(state)? {
+        sample_now(state)?;
     }
 
-    (total_read_bytes, total_write_bytes)
+    let collector = state.collector().lock().map_err(|_| ApiError::Internal)?;
+    collector.snapshot_since(since_ms).ok_or(ApiError::Internal)
 }
 
-fn push_point(series: &mut Vec<MetricPoint>, point: MetricPoint) {
-    series.push(point);
-    if series.len() > HISTORY_CAP {
-        let drop = series.len() - HISTORY_CAP;
-        series.drain(0..drop);
+pub fn bootstrap_metrics(state: &AppState) -> Result<MetricsSnapshot, ApiError> {
+    if !has_sample(state)? {
+        sample_now(state)?;
     }
-}
-
-pub fn get_metrics_snapshot(_state: &AppState) -> Result<MetricsSnapshot, ApiError> {
-    let cpu_raw = fs::read_to_string("/proc/stat").map_err(|_| ApiError::Internal)?;
-    let mem_raw = fs::read_to_string("/proc/meminfo").map_err(|_| ApiError::Internal)?;
-    let net_raw = fs::read_to_string("/proc/net/dev").unwrap_or_default();
-    let disk_raw = fs::read_to_string("/proc/diskstats").unwrap_or_default();
-
-    let cpu = parse_cpu_totals(&cpu_raw).ok_or(ApiError::Internal)?;
-    let mem = parse_meminfo(&mem_raw).ok_or(ApiError::Internal)?;
 
-    let now = now_ms();
-    let cpu_percent = if cpu.total > 0 {
-        ((cpu.total.saturating_sub(cpu.idle)) as f64 / cpu.total as f64 * 100.0) as f32
-    } else {
-        0.0
-    };
-
-    let ram_total_bytes = mem.mem_total_kb.saturating_mul(1024);
-    let ram_used_bytes = mem
-        .mem_total_kb
-        .saturating_sub(mem.mem_available_kb)
-        .saturating_mul(1024);
-    let swap_total_bytes = mem.swap_total_kb.saturating_mul(1024);
-    let swap_used_bytes = mem
-        .swap_total_kb
-        .saturating_sub(mem.swap_free_kb)
-        .saturating_mul(1024);
-
-    let (net_rx_total, net_tx_total) = parse_network_totals(&net_raw);
-    let (disk_read_total, disk_write_total) = parse_disk_totals(&disk_raw);
-
-    let mut net_rx_bps = 0.0_f64;
-    let mut net_tx_bps = 0.0_f64;
-    let mut disk_read_bps = 0.0_f64;
-    let mut disk_write_bps = 0.0_f64;
-
-    let last_lock = LAST_SAMPLE.get_or_init(|| Mutex::new(None));
-    if let Ok(mut last_guard) = last_lock.lock() {
-        if let Some(last) = *last_guard {
-            let dt_ms = (now - last.ts_ms).max(1) as f64;
-            net_rx_bps = (net_rx_total.saturating_sub(last.net_rx) as f64) * 1000.0 / dt_ms;
-            net_tx_bps = (net_tx_total.saturating_sub(last.net_tx) as f64) * 1000.0 / dt_ms;
-            disk_read_bps =
-                (disk_read_total.saturating_sub(last.disk_read) as f64) * 1000.0 / dt_ms;
-            disk_write_bps =
-                (disk_write_total.saturating_sub(last.disk_write) as f64) * 1000.0 / dt_ms;
-        }
+    let collector = state.collector().lock().map_err(|_| ApiError::Internal)?;
+    collector.bootstrap().ok_or(ApiError::Internal)
+}
 
-        *last_guard = Some(LastSample {
-            ts_ms: now,
-            net_rx: net_rx_total,
-            net_tx: net_tx_total,
-            disk_read: disk_read_total,
-            disk_write: disk_write_total,
-        });
+pub fn start_sampler<F>(state: &AppState, interval_ms: Option<u64>, emit: F) -> Result<u64, ApiError>
+where
+    F: Fn(&MetricsSnapshot) + Send + 'static,
+{
+    let interval_ms = normalize_interval_ms(interval_ms.unwrap_or(1_000));
+    let mut sampler = state.sampler().lock().map_err(|_| ApiError::Internal)?;
+    if let Some(running) = sampler.as_mut() {
+        running.set_interval(Duration::from_millis(interval_ms));
+        return Ok(interval_ms);
     }
 
-    let history_lock = HISTORY.get_or_init(|| Mutex::new(History::default()));
-    if let Ok(mut h) = history_lock.lock() {
-        let ram_percent = if ram_total_bytes > 0 {
-            (ram_used_bytes as f64 / ram_total_bytes as f64) * 100.0
-        } else {
-            0.0
+    let collector = state.collector().clone();
+    let store = state.store().clone();
+    *sampler = Some(Sampler::start(Duration::from_millis(interval_ms), move || {
+        let Ok(sample) = read_proc_sample() else {
+            return;
         };
+        // Each event carries just this sample's points; listeners merge them.
+        if let Some(snapshot) = ingest_sample(&collector, &store, &sample) {
+            emit(&snapshot);
+        }
+    }));
+    Ok(interval_ms)
+}
 
-        push_point(&mut h.cpu, MetricPoint { ts_ms: now, value: cpu_percent as f64 });
-        push_point(&mut h.ram, MetricPoint { ts_ms: now, value: ram_percent });
-        push_point(
-            &mut h.disk_read,
-            MetricPoint {
-                ts_ms: now,
-                value: disk_read_bps,
-            },
-        );
-        push_point(
-            &mut h.disk_write,
-            MetricPoint {
-                ts_ms: now,
-                value: disk_write_bps,
-            },
-        );
-        push_point(
-            &mut h.net_rx,
-            MetricPoint {
-                ts_ms: now,
-                value: net_rx_bps,
-            },
-        );
-        push_point(
-            &mut h.net_tx,
-            MetricPoint {
-                ts_ms: now,
-                value: net_tx_bps,
-            },
-        );
-
-        return Ok(MetricsSnapshot {
-            cpu_percent,
-            ram_used_bytes,
-            ram_total_bytes,
-            swap_used_bytes,
-            swap_total_bytes,
-            disk_read_bps,
-            disk_write_bps,
-            net_rx_bps,
-            net_tx_bps,
-            cpu_history: h.cpu.clone(),
-            ram_history: h.ram.clone(),
-            disk_read_history: h.disk_read.clone(),
-            disk_write_history: h.disk_write.clone(),
-            net_rx_history: h.net_rx.clone(),
-            net_tx_history: h.net_tx.clone(),
-        });
+pub fn stop_sampler(state: &AppState) -> Result<(), ApiError> {
+    let running = state.sampler().lock().map_err(|_| ApiError::Internal)?.take();
+    if let Some(sampler) = running {
+        sampler.stop();
     }
+    Ok(())
+}
 
-    Err(ApiError::Internal)
+pub fn set_sampler_interval(state: &AppState, interval_ms: u64) -> Result<u64, ApiError> {
+    let interval_ms = normalize_interval_ms(interval_ms);
+    let mut sampler = state.sampler().lock().map_err(|_| ApiError::Internal)?;
+    let running = sampler.as_mut().ok_or(ApiError::NotRunning)?;
+    running.set_interval(Duration::from_millis(interval_ms));
+    Ok(interval_ms)
 }
@@ -1,2 +1,6 @@
+pub mod export;
+pub mod filesystems;
+pub mod history;
+pub mod interrupts;
 pub mod metrics;
 pub mod processes;
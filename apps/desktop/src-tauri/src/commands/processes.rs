@@ -1,61 +1,223 @@
 use crate::{
     app_state::AppState,
     errors::ApiError,
-    system::models::ProcessRow,
-    system::processes::ProcessQuery,
-    system::signal::validate_permission,
+    system::clock::SampleTime,
+    system::models::{ProcessRow, TerminateEvent, TerminateStage},
+    system::processes::{parse_proc_stat, ProcessQuery},
+    system::signal::{
+        list_signals as signal_catalog, parse_signal, parse_status_uids, process_state, read_process_uids,
+        signal_name, validate_permission, verify_identity, PidFd, SignalError, SignalInfo, SignalPolicyStatus,
+        SignalTarget,
+    },
 };
 use serde::Deserialize;
-use std::{cmp::Ordering, collections::HashMap, fs};
+use std::{
+    cmp::Ordering,
+    collections::HashMap,
+    fs,
+    path::Path,
+    thread,
+    time::{Duration, Instant},
+};
+
+pub const TERMINATE_EVENT: &str = "processes://terminate";
+const DEFAULT_GRACE_MS: u64 = 5_000;
+const MAX_GRACE_MS: u64 = 120_000;
+// How long a process gets to disappear after SIGKILL before it is reported
+// as still running.
+const KILL_WAIT_MS: u64 = 2_000;
+const PROGRESS_INTERVAL_MS: u64 = 500;
+
+// Deserializes from a signal number or any name `parse_signal` accepts, so
+// the older "Sigterm"-style names keep working.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "SignalSpec")]
+pub struct ProcessSignal(i32);
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SignalSpec {
+    Number(i32),
+    Name(String),
+}
+
+impl TryFrom<SignalSpec> for ProcessSignal {
+    type Error = ApiError;
+
+    fn try_from(spec: SignalSpec) -> Result<Self, ApiError> {
+        match spec {
+            SignalSpec::Number(number) => signal_name(number).map(|_| Self(number)).ok_or(ApiError::InvalidSignal),
+            SignalSpec::Name(name) => Self::parse(&name),
+        }
+    }
+}
+
+impl ProcessSignal {
+    pub const TERM: Self = Self(libc::SIGTERM);
+    pub const KILL: Self = Self(libc::SIGKILL);
+
+    pub fn parse(spec: &str) -> Result<Self, ApiError> {
+        parse_signal(spec).map(Self).ok_or(ApiError::InvalidSignal)
+    }
+
+    pub fn number(self) -> i32 {
+        self.0
+    }
+}
+
+fn signal_error(error: SignalError) -> ApiError {
+    match error {
+        SignalError::NotFound => ApiError::NotFound,
+        SignalError::PermissionDenied | SignalError::InvalidPid => ApiError::PermissionDenied,
+        SignalError::ProcessChanged => ApiError::ProcessChanged,
+        SignalError::InvalidSignal => ApiError::InvalidSignal,
+        SignalError::Unsupported | SignalError::Failed => ApiError::Internal,
+    }
+}
+
+pub fn list_signals(state: &AppState) -> Result<Vec<SignalInfo>, ApiError> {
+    let policy = state.signal_policy().lock().map_err(|_| ApiError::Internal)?;
+    Ok(signal_catalog(&policy))
+}
 
-#[derive(Debug, Clone, Copy, Deserialize)]
-pub enum ProcessSignal {
-    Sigterm,
-    Sigkill,
-    Sigstop,
-    Sigcont,
+pub fn get_signal_policy(state: &AppState) -> Result<SignalPolicyStatus, ApiError> {
+    Ok(SignalPolicyStatus {
+        policy: state.signal_policy().lock().map_err(|_| ApiError::Internal)?.clone(),
+        source: state.signal_policy_source().clone(),
+    })
 }
 
+// Checks that the policy admits `signal`, that `pid` is still the process
+// that was listed with `start_time`, and that the caller may signal it.
 pub fn send_process_signal_inner(
     state: &AppState,
     pid: i32,
-    _signal: ProcessSignal,
+    start_time: u64,
+    signal: ProcessSignal,
 ) -> Result<(), ApiError> {
-    validate_permission(state.current_uid(), state.fake_target_uid(), pid).map_err(|_| ApiError::PermissionDenied)
+    let policy = state.signal_policy().lock().map_err(|_| ApiError::Internal)?;
+    if !policy.permits(signal.number()) {
+        return Err(ApiError::SignalNotAllowed);
+    }
+    drop(policy);
+    verify_identity(state.proc_root(), pid, start_time).map_err(signal_error)?;
+    let target = read_process_uids(state.proc_root(), pid).map_err(signal_error)?;
+    validate_permission(state.current_uid(), &target, pid).map_err(signal_error)
+}
+
+// Opens `pid` and runs the checks for every signal that may be sent to it.
+fn open_target(
+    state: &AppState,
+    pid: i32,
+    start_time: u64,
+    signals: &[ProcessSignal],
+) -> Result<SignalTarget, ApiError> {
+    let target = match PidFd::open(pid) {
+        // The pidfd pins the process, so once its start time checks out the
+        // signal cannot land on a recycled PID.
+        Ok(pidfd) => SignalTarget::PidFd(pidfd),
+        // Kernels before 5.3 have no pidfd; the identity is rechecked right
+        // before kill(2), which narrows the race without closing it.
+        Err(SignalError::Unsupported) => SignalTarget::Pid {
+            pid,
+            start_time,
+            proc_root: state.proc_root().to_path_buf(),
+        },
+        Err(error) => return Err(signal_error(error)),
+    };
+    for signal in signals {
+        send_process_signal_inner(state, pid, start_time, *signal)?;
+    }
+    Ok(target)
 }
 
 pub fn send_process_signal(
     state: &AppState,
     pid: i32,
+    start_time: u64,
     signal: ProcessSignal,
 ) -> Result<(), ApiError> {
-    send_process_signal_inner(state, pid, signal)?;
+    open_target(state, pid, start_time, &[signal])?
+        .send(signal.number())
+        .map_err(signal_error)
+}
 
-    let sig = match signal {
-        ProcessSignal::Sigterm => libc::SIGTERM,
-        ProcessSignal::Sigkill => libc::SIGKILL,
-        ProcessSignal::Sigstop => libc::SIGSTOP,
-        ProcessSignal::Sigcont => libc::SIGCONT,
-    };
+// Sends SIGTERM, then SIGKILL if the process is still around after
+// `grace_ms`. Errors before SIGTERM goes out are returned; everything after
+// is reported through `emit`, ending with Exited, Killed, StillRunning or
+// Failed. Returns the grace period actually used.
+pub fn terminate_process<F>(
+    state: &AppState,
+    pid: i32,
+    start_time: u64,
+    grace_ms: Option<u64>,
+    emit: F,
+) -> Result<u64, ApiError>
+where
+    F: Fn(&TerminateEvent) + Send + 'static,
+{
+    let grace_ms = grace_ms.unwrap_or(DEFAULT_GRACE_MS).min(MAX_GRACE_MS);
+    // KILL is checked along with TERM so a policy that denies it fails here
+    // rather than after the grace period.
+    let target = open_target(state, pid, start_time, &[ProcessSignal::TERM, ProcessSignal::KILL])?;
+    target.send(libc::SIGTERM).map_err(signal_error)?;
 
-    let rc = unsafe { libc::kill(pid, sig) };
-    if rc == 0 {
-        return Ok(());
-    }
+    let proc_root = state.proc_root().to_path_buf();
+    thread::spawn(move || {
+        let started = Instant::now();
+        escalate(&target, &proc_root, pid, start_time, grace_ms, |stage, process_state, error| {
+            emit(&TerminateEvent {
+                pid,
+                start_time,
+                stage,
+                elapsed_ms: started.elapsed().as_millis() as u64,
+                grace_ms,
+                process_state,
+                error,
+            })
+        });
+    });
+    Ok(grace_ms)
+}
 
-    match std::io::Error::last_os_error().raw_os_error() {
-        Some(code) if code == libc::EPERM => Err(ApiError::PermissionDenied),
-        Some(code) if code == libc::ESRCH => Err(ApiError::NotFound),
-        _ => Err(ApiError::Internal),
+// Watches the process after SIGTERM, reporting each stage through `report`.
+fn escalate<R>(target: &SignalTarget, proc_root: &Path, pid: i32, start_time: u64, grace_ms: u64, report: R)
+where
+    R: Fn(TerminateStage, Option<char>, Option<String>),
+{
+    report(TerminateStage::TermSent, None, None);
+    let deadline = Instant::now() + Duration::from_millis(grace_ms);
+    loop {
+        let left = deadline.saturating_duration_since(Instant::now());
+        if target.wait_exit(left.min(Duration::from_millis(PROGRESS_INTERVAL_MS))) {
+            report(TerminateStage::Exited, None, None);
+            return;
+        }
+        if Instant::now() >= deadline {
+            break;
+        }
+        report(TerminateStage::Waiting, None, None);
     }
-}
 
-fn parse_uid(status: &str) -> Option<u32> {
-    status
-        .lines()
-        .find(|line| line.starts_with("Uid:"))
-        .and_then(|line| line.split_whitespace().nth(1))
-        .and_then(|raw| raw.parse::<u32>().ok())
+    match target.send(libc::SIGKILL) {
+        Ok(()) => report(TerminateStage::KillSent, None, None),
+        // It exited between the last check and SIGKILL.
+        Err(SignalError::NotFound | SignalError::ProcessChanged) => {
+            report(TerminateStage::Exited, None, None);
+            return;
+        }
+        Err(error) => {
+            report(TerminateStage::Failed, None, Some(signal_error(error).to_string()));
+            return;
+        }
+    }
+    if target.wait_exit(Duration::from_millis(KILL_WAIT_MS)) {
+        report(TerminateStage::Killed, None, None);
+    } else {
+        // SIGKILL only lands once the process returns from the kernel, so
+        // one stuck in uninterruptible sleep (D) outlives it.
+        report(TerminateStage::StillRunning, process_state(proc_root, pid, start_time), None);
+    }
 }
 
 fn load_uid_map() -> HashMap<u32, String> {
@@ -96,20 +258,6 @@ fn read_process_command(pid: i32) -> String {
         .to_string()
 }
 
-fn parse_proc_stat_values(stat_raw: &str) -> Option<(u64, u64, u64)> {
-    let rparen = stat_raw.rfind(')')?;
-    let rest = stat_raw.get(rparen + 2..)?;
-    let cols: Vec<&str> = rest.split_whitespace().collect();
-    if cols.len() < 22 {
-        return None;
-    }
-
-    let utime = cols.get(11)?.parse::<u64>().ok()?;
-    let stime = cols.get(12)?.parse::<u64>().ok()?;
-    let rss_pages = cols.get(21)?.parse::<u64>().ok()?;
-    Some((utime, stime, rss_pages))
-}
-
 fn parse_total_mem_bytes() -> u64 {
     if let Ok(meminfo) = fs::read_to_string("/proc/meminfo") {
         for line in meminfo.lines() {
@@ -125,21 +273,14 @@ fn parse_total_mem_bytes() -> u64 {
     1
 }
 
-fn parse_uptime_seconds() -> f64 {
-    fs::read_to_string("/proc/uptime")
-        .ok()
-        .and_then(|raw| raw.split_whitespace().next().and_then(|v| v.parse::<f64>().ok()))
-        .unwrap_or(1.0)
-}
-
-pub fn list_processes(query: Option<ProcessQuery>) -> Result<Vec<ProcessRow>, ApiError> {
+pub fn list_processes(state: &AppState, query: Option<ProcessQuery>) -> Result<Vec<ProcessRow>, ApiError> {
     let uid_map = load_uid_map();
     let total_mem = parse_total_mem_bytes() as f64;
-    let uptime = parse_uptime_seconds().max(1.0);
-    let hz = unsafe { libc::sysconf(libc::_SC_CLK_TCK) as f64 }.max(1.0);
     let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) as u64 }.max(1) as f64;
+    let cpus = unsafe { libc::sysconf(libc::_SC_NPROCESSORS_ONLN) }.max(1) as usize;
 
     let mut rows: Vec<ProcessRow> = Vec::new();
+    let mut stats = Vec::new();
 
     let proc_entries = fs::read_dir("/proc").map_err(|_| ApiError::Internal)?;
     for entry in proc_entries.flatten() {
@@ -158,20 +299,18 @@ pub fn list_processes(query: Option<ProcessQuery>) -> Result<Vec<ProcessRow>, Ap
             continue;
         }
 
-        let Some((utime, stime, rss_pages)) = parse_proc_stat_values(&stat) else {
+        let Some(proc_stat) = parse_proc_stat(&stat) else {
             continue;
         };
 
-        let total_proc_seconds = (utime + stime) as f64 / hz;
-        let cpu_percent = ((total_proc_seconds / uptime) * 100.0) as f32;
-        let rss_bytes = (rss_pages as f64) * page_size;
+        let rss_bytes = (proc_stat.rss_pages as f64) * page_size;
         let mem_percent = if total_mem > 0.0 {
             ((rss_bytes / total_mem) * 100.0) as f32
         } else {
             0.0
         };
 
-        let uid = parse_uid(&status).unwrap_or(0);
+        let uid = parse_status_uids(&status).map_or(0, |uids| uids.real);
         let user = uid_map
             .get(&uid)
             .cloned()
@@ -180,13 +319,24 @@ pub fn list_processes(query: Option<ProcessQuery>) -> Result<Vec<ProcessRow>, Ap
 
         rows.push(ProcessRow {
             pid,
+            start_time: proc_stat.start_time,
             user,
             command,
-            cpu_percent,
+            cpu_percent: 0.0,
+            cpu_core_percent: 0.0,
             mem_percent,
         });
+        stats.push(proc_stat);
     }
 
+    // CPU% is taken over the interval since the previous listing, so every
+    // process is sampled before any filtering or paging.
+    state
+        .processes()
+        .lock()
+        .map_err(|_| ApiError::Internal)?
+        .sample(&mut rows, &stats, SampleTime::now(), cpus);
+
     let query = query.unwrap_or(ProcessQuery {
         search: None,
         sort_by: Some("cpu".to_string()),
@@ -223,12 +373,191 @@ pub fn list_processes(query: Option<ProcessQuery>) -> Result<Vec<ProcessRow>, Ap
 #[cfg(test)]
 mod tests {
     use super::{ProcessSignal, send_process_signal_inner};
-    use crate::app_state::AppState;
+    use crate::{
+        app_state::AppState,
+        errors::ApiError,
+        system::{
+            models::{TerminateEvent, TerminateStage},
+            processes::parse_proc_stat,
+            signal::SignalPolicy,
+        },
+        test_support::TempDir,
+    };
+    use std::{
+        fs,
+        process::{Child, Command},
+        sync::mpsc,
+        time::Duration,
+    };
+
+    const START_TIME: u64 = 777;
+
+    // A fake /proc holding `<pid>/status` with the given UIDs and a `<pid>/stat`
+    // started at START_TIME.
+    fn fixture_proc_root(name: &str, processes: &[(i32, &str)]) -> TempDir {
+        let root = TempDir::new(&format!("proc-{name}"));
+        for (pid, uids) in processes {
+            fs::create_dir_all(root.join(pid.to_string())).unwrap();
+            fs::write(
+                root.join(pid.to_string()).join("status"),
+                format!("Name:\tworker\nState:\tS (sleeping)\nUid:\t{uids}\nGid:\t1000\t1000\t1000\t1000\n"),
+            )
+            .unwrap();
+            fs::write(
+                root.join(pid.to_string()).join("stat"),
+                format!("{pid} (worker) S 1 {pid} {pid} 0 -1 4194304 90 0 0 0 0 0 0 0 20 0 1 0 {START_TIME} 1000 100 0\n"),
+            )
+            .unwrap();
+        }
+        root
+    }
 
     #[test]
     fn send_process_signal_rejects_foreign_uid() {
-        let state = AppState::new_for_tests(1000, 1001);
-        let result = send_process_signal_inner(&state, 4242, ProcessSignal::Sigterm);
-        assert!(result.is_err());
+        let root = fixture_proc_root("foreign", &[(4242, "1001\t1001\t1001\t1001")]);
+        let state = AppState::new_for_tests(1000).with_proc_root_for_tests(&root);
+        let result = send_process_signal_inner(&state, 4242, START_TIME, ProcessSignal::TERM);
+        assert!(matches!(result, Err(ApiError::PermissionDenied)));
+    }
+
+    #[test]
+    fn send_process_signal_checks_the_target_status_file() {
+        let root = fixture_proc_root(
+            "owned",
+            &[(4242, "1000\t1000\t1000\t1000"), (4343, "1000\t0\t0\t0"), (4444, "0\t1000\t0\t0")],
+        );
+        let state = AppState::new_for_tests(1000).with_proc_root_for_tests(&root);
+        let signal = |pid| send_process_signal_inner(&state, pid, START_TIME, ProcessSignal::TERM);
+        let own = signal(4242);
+        let setuid_child = signal(4343);
+        let root_daemon = signal(4444);
+        let vanished = signal(4545);
+
+        assert!(own.is_ok());
+        assert!(setuid_child.is_ok());
+        assert!(matches!(root_daemon, Err(ApiError::PermissionDenied)));
+        assert!(matches!(vanished, Err(ApiError::NotFound)));
+    }
+
+    #[test]
+    fn send_process_signal_refuses_a_recycled_pid() {
+        let root = fixture_proc_root("recycled", &[(4242, "1000\t1000\t1000\t1000")]);
+        let state = AppState::new_for_tests(1000).with_proc_root_for_tests(&root);
+        let result = send_process_signal_inner(&state, 4242, START_TIME - 1, ProcessSignal::TERM);
+        assert!(matches!(result, Err(ApiError::ProcessChanged)));
+    }
+
+    #[test]
+    fn send_process_signal_applies_the_signal_policy() {
+        let root = fixture_proc_root("policy", &[(4242, "1000\t1000\t1000\t1000")]);
+        let state = AppState::new_for_tests(1000).with_proc_root_for_tests(&root);
+        *state.signal_policy().lock().unwrap() = SignalPolicy {
+            allow: Vec::new(),
+            deny: vec!["SIGKILL".into()],
+        };
+
+        let hup = ProcessSignal::parse("SIGHUP").unwrap();
+        let allowed = send_process_signal_inner(&state, 4242, START_TIME, hup);
+        let denied = send_process_signal_inner(&state, 4242, START_TIME, ProcessSignal::KILL);
+
+        assert!(allowed.is_ok());
+        assert!(matches!(denied, Err(ApiError::SignalNotAllowed)));
+        assert!(matches!(ProcessSignal::parse("SIGBOGUS"), Err(ApiError::InvalidSignal)));
+        let listed = super::list_signals(&state).unwrap();
+        assert!(!listed.iter().find(|info| info.number == libc::SIGKILL).unwrap().allowed);
+    }
+
+    #[test]
+    fn process_signal_deserializes_from_names_and_numbers() {
+        use serde::{de::value::Error, de::IntoDeserializer, Deserialize};
+        let from_str = |spec: &str| ProcessSignal::deserialize(IntoDeserializer::<Error>::into_deserializer(spec));
+        let from_number = |number: i32| ProcessSignal::deserialize(IntoDeserializer::<Error>::into_deserializer(number));
+
+        assert_eq!(from_str("Sigterm").unwrap(), ProcessSignal::TERM);
+        assert_eq!(from_str("SIGUSR1").unwrap().number(), libc::SIGUSR1);
+        assert_eq!(from_number(9).unwrap(), ProcessSignal::KILL);
+        assert!(from_str("SIGNOPE").is_err());
+        assert!(from_number(0).is_err());
+    }
+
+    fn start_time_of(child: &Child) -> u64 {
+        let stat = fs::read_to_string(format!("/proc/{}/stat", child.id())).unwrap();
+        parse_proc_stat(&stat).unwrap().start_time
+    }
+
+    // Runs terminate_process against a live child and returns its events.
+    fn terminate(state: &AppState, child: &Child, grace_ms: u64) -> Result<Vec<TerminateEvent>, ApiError> {
+        let (tx, rx) = mpsc::channel();
+        let pid = child.id() as i32;
+        super::terminate_process(state, pid, start_time_of(child), Some(grace_ms), move |event| {
+            let _ = tx.send(event.clone());
+        })?;
+        // The channel closes when the watcher thread finishes and drops `tx`.
+        Ok(rx.iter().collect())
+    }
+
+    fn stages(events: &[TerminateEvent]) -> Vec<TerminateStage> {
+        events.iter().map(|event| event.stage).collect()
+    }
+
+    #[test]
+    fn terminate_process_stops_after_term_and_checks_kill_up_front() {
+        let state = AppState::new_for_tests(unsafe { libc::geteuid() });
+        let mut child = Command::new("sleep").arg("30").spawn().unwrap();
+
+        *state.signal_policy().lock().unwrap() = SignalPolicy {
+            allow: Vec::new(),
+            deny: vec!["SIGKILL".into()],
+        };
+        let refused = terminate(&state, &child, 1_000);
+        *state.signal_policy().lock().unwrap() = SignalPolicy::default();
+        let events = terminate(&state, &child, 1_000);
+        let _ = child.kill();
+        let status = child.wait().unwrap();
+
+        assert!(matches!(refused, Err(ApiError::SignalNotAllowed)));
+        let events = events.unwrap();
+        assert_eq!(stages(&events), [TerminateStage::TermSent, TerminateStage::Exited]);
+        assert_eq!(std::os::unix::process::ExitStatusExt::signal(&status), Some(libc::SIGTERM));
+    }
+
+    #[test]
+    fn terminate_process_escalates_when_term_is_ignored() {
+        let state = AppState::new_for_tests(unsafe { libc::geteuid() });
+        let mut child = Command::new("sh")
+            .args(["-c", "trap '' TERM; while :; do sleep 1; done"])
+            .spawn()
+            .unwrap();
+        // Wait for the shell to install the trap, or TERM would just end it.
+        let ignores_term = || {
+            fs::read_to_string(format!("/proc/{}/status", child.id()))
+                .ok()
+                .and_then(|status| {
+                    let mask = status.lines().find_map(|line| line.strip_prefix("SigIgn:"))?.trim().to_string();
+                    u64::from_str_radix(&mask, 16).ok()
+                })
+                .is_some_and(|mask| mask & (1 << (libc::SIGTERM - 1)) != 0)
+        };
+        for _ in 0..100 {
+            if ignores_term() {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        let events = terminate(&state, &child, 600).unwrap();
+        let status = child.wait().unwrap();
+
+        assert_eq!(
+            stages(&events),
+            [
+                TerminateStage::TermSent,
+                TerminateStage::Waiting,
+                TerminateStage::KillSent,
+                TerminateStage::Killed,
+            ]
+        );
+        assert!(events.last().unwrap().elapsed_ms >= 600);
+        assert_eq!(std::os::unix::process::ExitStatusExt::signal(&status), Some(libc::SIGKILL));
     }
 }
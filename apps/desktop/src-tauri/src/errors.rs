@@ -4,7 +4,13 @@ use std::fmt;
 pub enum ApiError {
     PermissionDenied,
     InvalidSignal,
+    SignalNotAllowed,
     NotFound,
+    NotRunning,
+    InvalidSeries,
+    InvalidPath,
+    ExportDirUnavailable(String),
+    ProcessChanged,
     Internal,
 }
 
@@ -13,7 +19,13 @@ impl fmt::Display for ApiError {
         match self {
             Self::PermissionDenied => write!(f, "permission denied"),
             Self::InvalidSignal => write!(f, "invalid signal"),
+            Self::SignalNotAllowed => write!(f, "signal not allowed by policy"),
             Self::NotFound => write!(f, "process not found"),
+            Self::NotRunning => write!(f, "metrics sampler is not running"),
+            Self::InvalidSeries => write!(f, "unknown history series"),
+            Self::InvalidPath => write!(f, "export path is outside the export directory"),
+            Self::ExportDirUnavailable(reason) => write!(f, "export directory unavailable: {reason}"),
+            Self::ProcessChanged => write!(f, "process changed since it was listed"),
             Self::Internal => write!(f, "internal error"),
         }
     }
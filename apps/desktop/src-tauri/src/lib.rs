@@ -2,35 +2,145 @@ pub mod app_state;
 pub mod commands;
 pub mod errors;
 pub mod system;
+#[cfg(test)]
+mod test_support;
 
 use app_state::AppState;
 use commands::processes::ProcessSignal;
 use errors::ApiError;
-use system::models::{MetricsSnapshot, ProcessRow};
+use system::history::RetentionConfig;
+use system::models::{
+    ExportRequest, ExportSummary, FilesystemUsage, HistoryRange, HistorySeries, InterruptRates, MetricsSnapshot,
+    ProcessRow, Resolution,
+};
 use system::processes::ProcessQuery;
-use tauri::State;
+use system::signal::{SignalInfo, SignalPolicyStatus};
+use tauri::{AppHandle, Emitter, State};
 
 fn map_api_error(error: ApiError) -> String {
     error.to_string()
 }
 
 #[tauri::command]
-fn get_metrics_snapshot(state: State<'_, AppState>) -> Result<MetricsSnapshot, String> {
-    commands::metrics::get_metrics_snapshot(&state).map_err(map_api_error)
+fn get_metrics_snapshot(state: State<'_, AppState>, since_ms: Option<i64>) -> Result<MetricsSnapshot, String> {
+    commands::metrics::get_metrics_snapshot(&state, since_ms).map_err(map_api_error)
 }
 
 #[tauri::command]
-fn list_processes(query: Option<ProcessQuery>) -> Result<Vec<ProcessRow>, String> {
-    commands::processes::list_processes(query).map_err(map_api_error)
+fn bootstrap_metrics(state: State<'_, AppState>) -> Result<MetricsSnapshot, String> {
+    commands::metrics::bootstrap_metrics(&state).map_err(map_api_error)
+}
+
+#[tauri::command]
+fn start_metrics_sampler(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    interval_ms: Option<u64>,
+) -> Result<u64, String> {
+    commands::metrics::start_sampler(&state, interval_ms, move |snapshot| {
+        let _ = app.emit(commands::metrics::METRICS_EVENT, snapshot);
+    })
+    .map_err(map_api_error)
+}
+
+#[tauri::command]
+fn stop_metrics_sampler(state: State<'_, AppState>) -> Result<(), String> {
+    commands::metrics::stop_sampler(&state).map_err(map_api_error)
+}
+
+#[tauri::command]
+fn set_metrics_interval(state: State<'_, AppState>, interval_ms: u64) -> Result<u64, String> {
+    commands::metrics::set_sampler_interval(&state, interval_ms).map_err(map_api_error)
+}
+
+#[tauri::command]
+fn query_history(
+    state: State<'_, AppState>,
+    series: HistorySeries,
+    start_ms: Option<i64>,
+    end_ms: Option<i64>,
+    resolution: Option<Resolution>,
+) -> Result<HistoryRange, String> {
+    commands::history::query_history(&state, series, start_ms, end_ms, resolution.unwrap_or(Resolution::Auto))
+        .map_err(map_api_error)
+}
+
+#[tauri::command]
+fn get_history_retention(state: State<'_, AppState>) -> Result<RetentionConfig, String> {
+    commands::history::get_history_retention(&state).map_err(map_api_error)
+}
+
+#[tauri::command]
+fn set_history_retention(state: State<'_, AppState>, retention: RetentionConfig) -> Result<RetentionConfig, String> {
+    commands::history::set_history_retention(&state, retention).map_err(map_api_error)
+}
+
+#[tauri::command]
+fn list_export_series(state: State<'_, AppState>) -> Result<Vec<String>, String> {
+    commands::export::list_export_series(&state).map_err(map_api_error)
+}
+
+#[tauri::command]
+fn get_export_dir(state: State<'_, AppState>) -> Result<String, String> {
+    commands::export::get_export_dir(&state).map_err(map_api_error)
+}
+
+#[tauri::command]
+fn export_history(state: State<'_, AppState>, request: ExportRequest) -> Result<ExportSummary, String> {
+    commands::export::export_history(&state, &request).map_err(map_api_error)
+}
+
+#[tauri::command]
+fn list_filesystems(
+    state: State<'_, AppState>,
+    include_history: Option<bool>,
+) -> Result<Vec<FilesystemUsage>, String> {
+    commands::filesystems::list_filesystems(&state, include_history.unwrap_or(false))
+        .map_err(map_api_error)
+}
+
+#[tauri::command]
+fn get_interrupt_rates(state: State<'_, AppState>) -> Result<InterruptRates, String> {
+    commands::interrupts::get_interrupt_rates(&state).map_err(map_api_error)
+}
+
+#[tauri::command]
+fn list_processes(state: State<'_, AppState>, query: Option<ProcessQuery>) -> Result<Vec<ProcessRow>, String> {
+    commands::processes::list_processes(&state, query).map_err(map_api_error)
 }
 
 #[tauri::command]
 fn send_process_signal(
     state: State<'_, AppState>,
     pid: i32,
+    start_time: u64,
     signal: ProcessSignal,
 ) -> Result<(), String> {
-    commands::processes::send_process_signal(&state, pid, signal).map_err(map_api_error)
+    commands::processes::send_process_signal(&state, pid, start_time, signal).map_err(map_api_error)
+}
+
+#[tauri::command]
+fn terminate_process(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    pid: i32,
+    start_time: u64,
+    grace_ms: Option<u64>,
+) -> Result<u64, String> {
+    commands::processes::terminate_process(&state, pid, start_time, grace_ms, move |event| {
+        let _ = app.emit(commands::processes::TERMINATE_EVENT, event);
+    })
+    .map_err(map_api_error)
+}
+
+#[tauri::command]
+fn list_signals(state: State<'_, AppState>) -> Result<Vec<SignalInfo>, String> {
+    commands::processes::list_signals(&state).map_err(map_api_error)
+}
+
+#[tauri::command]
+fn get_signal_policy(state: State<'_, AppState>) -> Result<SignalPolicyStatus, String> {
+    commands::processes::get_signal_policy(&state).map_err(map_api_error)
 }
 
 pub fn run() {
@@ -38,8 +148,23 @@ pub fn run() {
         .manage(AppState::new())
         .invoke_handler(tauri::generate_handler![
             get_metrics_snapshot,
+            bootstrap_metrics,
+            start_metrics_sampler,
+            stop_metrics_sampler,
+            set_metrics_interval,
+            query_history,
+            get_history_retention,
+            set_history_retention,
+            list_export_series,
+            get_export_dir,
+            export_history,
+            list_filesystems,
+            get_interrupt_rates,
             list_processes,
-            send_process_signal
+            send_process_signal,
+            terminate_process,
+            list_signals,
+            get_signal_policy
         ])
         .run(tauri::generate_context!())
         .expect("failed to run tauri app");
@@ -0,0 +1,136 @@
+use std::{fs, path::Path};
+
+use crate::system::models::BlockDeviceKind;
+
+#[derive(Debug, Clone)]
+pub struct BlockDevice {
+    pub name: String,
+    pub kind: BlockDeviceKind,
+    pub label: Option<String>,
+    pub parent: Option<String>,
+}
+
+fn disk_kind(name: &str, dir: &Path) -> (BlockDeviceKind, Option<String>) {
+    if dir.join("dm").is_dir() {
+        let label = fs::read_to_string(dir.join("dm/name"))
+            .ok()
+            .map(|raw| raw.trim().to_string())
+            .filter(|raw| !raw.is_empty());
+        return (BlockDeviceKind::DeviceMapper, label);
+    }
+
+    let is_virtual = fs::canonicalize(dir)
+        .map(|path| path.to_string_lossy().contains("/devices/virtual/"))
+        .unwrap_or(false);
+    if is_virtual || ["loop", "ram", "zram"].iter().any(|prefix| name.starts_with(prefix)) {
+        return (BlockDeviceKind::Virtual, None);
+    }
+    if name.starts_with("sr") {
+        return (BlockDeviceKind::Optical, None);
+    }
+
+    (BlockDeviceKind::Disk, None)
+}
+
+pub fn read_block_devices(sys_root: &Path) -> Vec<BlockDevice> {
+    let mut devices = Vec::new();
+    let Ok(entries) = fs::read_dir(sys_root.join("block")) else {
+        return devices;
+    };
+
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let dir = entry.path();
+        let (kind, label) = disk_kind(&name, &dir);
+
+        if let Ok(children) = fs::read_dir(&dir) {
+            for child in children.flatten() {
+                if !child.path().join("partition").is_file() {
+                    continue;
+                }
+                devices.push(BlockDevice {
+                    name: child.file_name().to_string_lossy().to_string(),
+                    kind: BlockDeviceKind::Partition,
+                    label: None,
+                    parent: Some(name.clone()),
+                });
+            }
+        }
+
+        devices.push(BlockDevice {
+            name,
+            kind,
+            label,
+            parent: None,
+        });
+    }
+
+    devices.sort_by(|a, b| a.name.cmp(&b.name));
+    devices
+}
+
+pub fn guess_block_device(name: &str, known: &[&str]) -> BlockDevice {
+    // Without sysfs, treat `sda1` / `nvme0n1p1` as partitions of a listed parent.
+    let trimmed = name.trim_end_matches(|c: char| c.is_ascii_digit());
+    let parent = [trimmed, trimmed.strip_suffix('p').unwrap_or(trimmed)]
+        .into_iter()
+        .find(|candidate| *candidate != name && known.contains(candidate))
+        .map(str::to_string);
+
+    let kind = if parent.is_some() {
+        BlockDeviceKind::Partition
+    } else if name.starts_with("dm-") {
+        BlockDeviceKind::DeviceMapper
+    } else if ["loop", "ram", "zram"].iter().any(|prefix| name.starts_with(prefix)) {
+        BlockDeviceKind::Virtual
+    } else if name.starts_with("sr") {
+        BlockDeviceKind::Optical
+    } else {
+        BlockDeviceKind::Disk
+    };
+
+    BlockDevice {
+        name: name.to_string(),
+        kind,
+        label: None,
+        parent,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::{system::models::BlockDeviceKind, test_support::TempDir};
+
+    #[test]
+    fn read_block_devices_separates_disks_partitions_and_dm() {
+        let root = TempDir::new("block");
+        fs::create_dir_all(root.join("block/sda/sda1")).unwrap();
+        fs::write(root.join("block/sda/sda1/partition"), "1\n").unwrap();
+        fs::create_dir_all(root.join("block/dm-0/dm")).unwrap();
+        fs::write(root.join("block/dm-0/dm/name"), "vg0-root\n").unwrap();
+        fs::create_dir_all(root.join("block/loop0")).unwrap();
+        fs::create_dir_all(root.join("block/sr0")).unwrap();
+
+        let devices = super::read_block_devices(&root);
+
+        let find = |name: &str| devices.iter().find(|d| d.name == name).unwrap();
+        assert_eq!(find("sda").kind, BlockDeviceKind::Disk);
+        assert_eq!(find("sda1").kind, BlockDeviceKind::Partition);
+        assert_eq!(find("sda1").parent.as_deref(), Some("sda"));
+        assert_eq!(find("dm-0").kind, BlockDeviceKind::DeviceMapper);
+        assert_eq!(find("dm-0").label.as_deref(), Some("vg0-root"));
+        assert_eq!(find("loop0").kind, BlockDeviceKind::Virtual);
+        assert_eq!(find("sr0").kind, BlockDeviceKind::Optical);
+    }
+
+    #[test]
+    fn guess_block_device_spots_partitions_by_name() {
+        let known = ["sda", "sda1", "nvme0n1", "nvme0n1p2"];
+        assert_eq!(super::guess_block_device("sda1", &known).kind, BlockDeviceKind::Partition);
+        assert_eq!(super::guess_block_device("nvme0n1p2", &known).parent.as_deref(), Some("nvme0n1"));
+        assert_eq!(super::guess_block_device("nvme0n1", &known).kind, BlockDeviceKind::Disk);
+        assert_eq!(super::guess_block_device("sr0", &known).kind, BlockDeviceKind::Optical);
+    }
+}
@@ -3,6 +3,7 @@ use std::collections::HashMap;
 pub struct ProcessCacheEntry {
     pub command: String,
     pub last_seen_ms: i64,
+    pub start_time: u64,
     pub last_proc_jiffies: u64,
 }
 
@@ -26,6 +27,7 @@ impl ProcessCache {
             ProcessCacheEntry {
                 command,
                 last_seen_ms: now_ms,
+                start_time: 0,
                 last_proc_jiffies: 0,
             },
         );
@@ -40,15 +42,30 @@ impl ProcessCache {
         Some(entry.command.as_str())
     }
 
-    pub fn upsert_with_jiffies(&mut self, pid: i32, command: String, now_ms: i64, jiffies: u64) {
-        self.map.insert(
+    // Returns the jiffies recorded for this process last time, or None when
+    // the PID is new or now belongs to a different process (other start time).
+    pub fn upsert_with_jiffies(
+        &mut self,
+        pid: i32,
+        command: String,
+        now_ms: i64,
+        start_time: u64,
+        jiffies: u64,
+    ) -> Option<u64> {
+        let previous = self.map.insert(
             pid,
             ProcessCacheEntry {
                 command,
                 last_seen_ms: now_ms,
+                start_time,
                 last_proc_jiffies: jiffies,
             },
-        );
+        )?;
+        (previous.start_time == start_time).then_some(previous.last_proc_jiffies)
+    }
+
+    pub fn evict_unseen(&mut self, now_ms: i64) {
+        self.map.retain(|_, entry| entry.last_seen_ms >= now_ms);
     }
 }
 
@@ -63,4 +80,19 @@ mod tests {
         let first = cache.get_command(100, 12_000).unwrap();
         assert_eq!(first, "python app.py");
     }
+
+    #[test]
+    fn cache_detects_pid_reuse_and_evicts_dead_pids() {
+        let mut cache = ProcessCache::new(5_000);
+        assert_eq!(cache.upsert_with_jiffies(100, "sleep".into(), 1_000, 500, 10), None);
+        assert_eq!(cache.upsert_with_jiffies(100, "sleep".into(), 2_000, 500, 30), Some(10));
+        // Same PID, different start time: a new process, no baseline.
+        assert_eq!(cache.upsert_with_jiffies(100, "make".into(), 3_000, 900, 5), None);
+
+        cache.upsert_with_jiffies(200, "bash".into(), 3_000, 700, 1);
+        cache.upsert_with_jiffies(200, "bash".into(), 4_000, 700, 1);
+        cache.evict_unseen(4_000);
+        assert_eq!(cache.get_command(100, 4_000), None);
+        assert_eq!(cache.get_command(200, 4_000), Some("bash"));
+    }
 }
@@ -0,0 +1,103 @@
+use std::{
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SampleTime {
+    pub wall_ms: u64,
+    pub monotonic_ms: u64,
+    pub boot_ms: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Elapsed {
+    pub running_ms: u64,
+    pub suspended_ms: u64,
+}
+
+fn system_wall_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn clock_ms(clock: libc::clockid_t) -> Option<u64> {
+    let mut ts = libc::timespec { tv_sec: 0, tv_nsec: 0 };
+    if unsafe { libc::clock_gettime(clock, &mut ts) } != 0 {
+        return None;
+    }
+    Some((ts.tv_sec as u64).saturating_mul(1_000) + ts.tv_nsec as u64 / 1_000_000)
+}
+
+impl SampleTime {
+    pub fn now() -> Self {
+        let monotonic_ms = clock_ms(libc::CLOCK_MONOTONIC).unwrap_or(0);
+        let boot_ms = clock_ms(libc::CLOCK_BOOTTIME).unwrap_or(monotonic_ms);
+
+        // Wall time stamps history and cursors, so it must never step back.
+        // It advances by CLOCK_BOOTTIME from the previous sample, so suspend
+        // still moves it forward, and only jumps ahead when the system clock
+        // is set forward (NTP fixing a clock that was behind at boot).
+        static LAST: Mutex<Option<(u64, u64)>> = Mutex::new(None);
+        let realtime_ms = clock_ms(libc::CLOCK_REALTIME).unwrap_or_else(system_wall_ms);
+        let mut last = LAST.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let wall_ms = match *last {
+            Some((wall_ms, last_boot_ms)) => realtime_ms.max(wall_ms + boot_ms.saturating_sub(last_boot_ms)),
+            None => realtime_ms,
+        };
+        *last = Some((wall_ms, boot_ms));
+        Self {
+            wall_ms,
+            monotonic_ms,
+            boot_ms,
+        }
+    }
+
+    pub fn at(ms: u64) -> Self {
+        Self {
+            wall_ms: ms,
+            monotonic_ms: ms,
+            boot_ms: ms,
+        }
+    }
+
+    pub fn since(&self, earlier: &SampleTime) -> Elapsed {
+        // CLOCK_MONOTONIC stops during suspend and CLOCK_BOOTTIME does not.
+        let running_ms = self.monotonic_ms.saturating_sub(earlier.monotonic_ms);
+        let total_ms = self.boot_ms.saturating_sub(earlier.boot_ms);
+        Elapsed {
+            running_ms,
+            suspended_ms: total_ms.saturating_sub(running_ms),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SampleTime;
+
+    #[test]
+    fn since_splits_running_and_suspended_time() {
+        let before = SampleTime::at(10_000);
+        let after = SampleTime {
+            wall_ms: 71_000,
+            monotonic_ms: 11_000,
+            boot_ms: 71_000,
+        };
+        let elapsed = after.since(&before);
+        assert_eq!(elapsed.running_ms, 1_000);
+        assert_eq!(elapsed.suspended_ms, 60_000);
+    }
+
+    #[test]
+    fn now_never_moves_backwards_and_keeps_up_with_the_system_clock() {
+        let first = SampleTime::now();
+        let second = SampleTime::now();
+        assert!(first.wall_ms + 1_000 > super::system_wall_ms());
+        assert!(second.wall_ms >= first.wall_ms);
+        assert!(second.monotonic_ms >= first.monotonic_ms);
+        assert!(second.boot_ms >= first.boot_ms);
+    }
+}
@@ -1,69 +1,776 @@
+use std::collections::{BTreeMap, HashMap};
+
 use crate::system::{
-    delta::cpu_percent,
+    block::{guess_block_device, BlockDevice},
+    clock::SampleTime,
+    cpufreq::{parse_cpufreq, CpuFreqRaw},
+    delta::{counter_delta, cpu_mode_percent, cpu_percent, rate_per_sec},
+    history::{RetentionConfig, TieredHistory, MIN_SAMPLE_MS},
     models::*,
-    procfs::{parse_cpu_totals, parse_meminfo},
+    power::parse_power_supply_uevent,
+    procfs::{
+        parse_core_totals, parse_cpu_totals, parse_diskstats, parse_loadavg, parse_meminfo,
+        parse_meminfo_map, parse_net_dev, parse_pressure, parse_stat_counters, parse_vmstat,
+        CpuTotals, DiskCounters, NetDevCounters, Pressure, StatCounters, VmStat,
+    },
     ring_buffer::RingBuffer,
+    sensors::SensorReading,
 };
 
+#[derive(Debug, Clone, Default)]
+pub struct ProcSample {
+    pub stat: String,
+    pub meminfo: String,
+    pub net_dev: String,
+    pub interface_indexes: HashMap<String, u32>,
+    pub diskstats: String,
+    pub block_devices: Vec<BlockDevice>,
+    pub loadavg: String,
+    pub pressure_cpu: Option<String>,
+    pub pressure_memory: Option<String>,
+    pub pressure_io: Option<String>,
+    pub sensors: Vec<SensorReading>,
+    pub cpufreq: Vec<CpuFreqRaw>,
+    pub power_supplies: Vec<String>,
+    pub vmstat: String,
+}
+
+struct CoreHistory {
+    usage: RingBuffer<MetricPoint>,
+    modes: CpuModes<RingBuffer<MetricPoint>>,
+    freq: RingBuffer<MetricPoint>,
+}
+
+impl CoreHistory {
+    fn new(cap: usize) -> Self {
+        Self {
+            usage: RingBuffer::new(cap),
+            modes: CpuModes::from_fn(|| RingBuffer::new(cap)),
+            freq: RingBuffer::new(cap),
+        }
+    }
+}
+
+struct DiskHistory {
+    read: RingBuffer<MetricPoint>,
+    write: RingBuffer<MetricPoint>,
+    busy: RingBuffer<MetricPoint>,
+}
+
+fn memory_breakdown(meminfo_raw: &str) -> MemoryBreakdown {
+    let map = parse_meminfo_map(meminfo_raw);
+    let hugepage_size_bytes = map.bytes("Hugepagesize");
+    let hugetlb_bytes = match map.bytes("Hugetlb") {
+        0 => map.count("HugePages_Total").saturating_mul(hugepage_size_bytes),
+        value => value,
+    };
+
+    let mut breakdown = MemoryBreakdown {
+        total_bytes: map.bytes("MemTotal"),
+        free_bytes: map.bytes("MemFree"),
+        available_bytes: map.bytes("MemAvailable"),
+        anon_bytes: map.bytes("AnonPages"),
+        cached_bytes: map.bytes("Cached"),
+        shmem_bytes: map.bytes("Shmem"),
+        buffers_bytes: map.bytes("Buffers"),
+        slab_reclaimable_bytes: map.bytes("SReclaimable"),
+        slab_unreclaimable_bytes: map.bytes("SUnreclaim"),
+        kernel_stack_bytes: map.bytes("KernelStack"),
+        page_tables_bytes: map.bytes("PageTables"),
+        hugetlb_bytes,
+        other_bytes: 0,
+        mapped_bytes: map.bytes("Mapped"),
+        dirty_bytes: map.bytes("Dirty"),
+        writeback_bytes: map.bytes("Writeback"),
+        committed_as_bytes: map.bytes("Committed_AS"),
+        commit_limit_bytes: map.bytes("CommitLimit"),
+        hugepages_total: map.count("HugePages_Total"),
+        hugepages_free: map.count("HugePages_Free"),
+        hugepage_size_bytes,
+        zswap_bytes: map.bytes("Zswap"),
+        zswapped_bytes: map.bytes("Zswapped"),
+    };
+
+    // Shmem is part of Cached and Mapped overlaps anon/cache, so neither is
+    // added here; whatever the kernel does not itemise ends up in `other`.
+    let accounted = breakdown.anon_bytes
+        + breakdown.cached_bytes
+        + breakdown.buffers_bytes
+        + breakdown.slab_reclaimable_bytes
+        + breakdown.slab_unreclaimable_bytes
+        + breakdown.kernel_stack_bytes
+        + breakdown.page_tables_bytes
+        + breakdown.hugetlb_bytes;
+    breakdown.other_bytes = breakdown
+        .total_bytes
+        .saturating_sub(breakdown.free_bytes)
+        .saturating_sub(accounted);
+    breakdown
+}
+
+struct VmHistory {
+    pgfault: RingBuffer<MetricPoint>,
+    pgmajfault: RingBuffer<MetricPoint>,
+    pswpin: RingBuffer<MetricPoint>,
+    pswpout: RingBuffer<MetricPoint>,
+    pgscan: RingBuffer<MetricPoint>,
+    pgsteal: RingBuffer<MetricPoint>,
+    compact_stall: RingBuffer<MetricPoint>,
+    oom_kill: RingBuffer<MetricPoint>,
+}
+
+impl VmHistory {
+    fn new(cap: usize) -> Self {
+        Self {
+            pgfault: RingBuffer::new(cap),
+            pgmajfault: RingBuffer::new(cap),
+            pswpin: RingBuffer::new(cap),
+            pswpout: RingBuffer::new(cap),
+            pgscan: RingBuffer::new(cap),
+            pgsteal: RingBuffer::new(cap),
+            compact_stall: RingBuffer::new(cap),
+            oom_kill: RingBuffer::new(cap),
+        }
+    }
+}
+
+struct LoadHistory {
+    load1: RingBuffer<MetricPoint>,
+    load5: RingBuffer<MetricPoint>,
+    load15: RingBuffer<MetricPoint>,
+    procs_running: RingBuffer<MetricPoint>,
+    procs_blocked: RingBuffer<MetricPoint>,
+    forks: RingBuffer<MetricPoint>,
+    ctxt: RingBuffer<MetricPoint>,
+    intr: RingBuffer<MetricPoint>,
+    softirq: RingBuffer<MetricPoint>,
+}
+
+impl LoadHistory {
+    fn new(cap: usize) -> Self {
+        Self {
+            load1: RingBuffer::new(cap),
+            load5: RingBuffer::new(cap),
+            load15: RingBuffer::new(cap),
+            procs_running: RingBuffer::new(cap),
+            procs_blocked: RingBuffer::new(cap),
+            forks: RingBuffer::new(cap),
+            ctxt: RingBuffer::new(cap),
+            intr: RingBuffer::new(cap),
+            softirq: RingBuffer::new(cap),
+        }
+    }
+}
+
+struct PressureHistory {
+    some: RingBuffer<MetricPoint>,
+    full: RingBuffer<MetricPoint>,
+}
+
+impl PressureHistory {
+    fn new(cap: usize) -> Self {
+        Self {
+            some: RingBuffer::new(cap),
+            full: RingBuffer::new(cap),
+        }
+    }
+}
+
+fn stall_percent(prev: u64, next: u64, delta_ms: f64) -> f64 {
+    // PSI totals are cumulative stall time in microseconds.
+    (rate_per_sec(prev, next, delta_ms) / 1_000_000.0 * 100.0).min(100.0)
+}
+
+fn pressure_stats(
+    history: &mut PressureHistory,
+    prev: Option<&Pressure>,
+    next: &Pressure,
+    delta_ms: f64,
+    ts_ms: i64,
+) -> PressureStats {
+    let full = next.full.unwrap_or_default();
+    let (some_stall_percent, full_stall_percent) = prev.map_or((0.0, 0.0), |prev| {
+        let prev_full = prev.full.unwrap_or_default();
+        (
+            stall_percent(prev.some.total_us, next.some.total_us, delta_ms),
+            stall_percent(prev_full.total_us, full.total_us, delta_ms),
+        )
+    });
+    history.some.push(MetricPoint {
+        ts_ms,
+        value: some_stall_percent,
+    });
+    history.full.push(MetricPoint {
+        ts_ms,
+        value: full_stall_percent,
+    });
+
+    PressureStats {
+        some_avg10: next.some.avg10,
+        some_avg60: next.some.avg60,
+        some_avg300: next.some.avg300,
+        some_stall_percent,
+        full_avg10: full.avg10,
+        full_avg60: full.avg60,
+        full_avg300: full.avg300,
+        full_stall_percent,
+        some_history: Vec::new(),
+        full_history: Vec::new(),
+    }
+}
+
+struct InterfaceHistory {
+    rx: RingBuffer<MetricPoint>,
+    tx: RingBuffer<MetricPoint>,
+}
+
+fn push_modes(history: &mut CpuModes<RingBuffer<MetricPoint>>, modes: &CpuModes<f32>, ts_ms: i64) {
+    history.zip_mut(modes, |series, value| {
+        series.push(MetricPoint {
+            ts_ms,
+            value: *value as f64,
+        })
+    });
+}
+
+// Anything shorter is scheduling jitter rather than a suspend/resume cycle.
+const SUSPEND_GAP_MIN_MS: u64 = 2_000;
+const MAX_GAPS: usize = 16;
+
+// Snapshots carry this much history. The six aggregate series are kept for
+// longer in `TieredHistory`; everything else (cores, devices, load, PSI,
+// vmstat, sensors, batteries) is live only and never older than this.
+pub const LIVE_WINDOW_MS: i64 = 60_000;
+const LIVE_CAP: usize = (LIVE_WINDOW_MS as u64 / MIN_SAMPLE_MS) as usize + 1;
+
 pub struct SystemCollector {
-    interval_ms: u64,
-    last_cpu: Option<(u64, u64)>,
-    last_net: Option<(u64, u64)>,
-    last_disk: Option<(u64, u64)>,
-    cpu_history: RingBuffer<MetricPoint>,
-    ram_history: RingBuffer<MetricPoint>,
-    disk_read_history: RingBuffer<MetricPoint>,
-    disk_write_history: RingBuffer<MetricPoint>,
-    net_rx_history: RingBuffer<MetricPoint>,
-    net_tx_history: RingBuffer<MetricPoint>,
+    history_cap: usize,
+    last_time: Option<SampleTime>,
+    last_cpu: Option<CpuTotals>,
+    last_cores: HashMap<usize, CpuTotals>,
+    last_interfaces: HashMap<String, NetDevCounters>,
+    last_interface_indexes: HashMap<String, u32>,
+    last_disks: HashMap<String, DiskCounters>,
+    last_stat_counters: Option<StatCounters>,
+    last_pressure: Option<[Pressure; 3]>,
+    last_vmstat: Option<VmStat>,
+    cpu_mode_history: CpuModes<RingBuffer<MetricPoint>>,
+    core_history: BTreeMap<usize, CoreHistory>,
+    interface_history: BTreeMap<String, InterfaceHistory>,
+    disk_history: BTreeMap<String, DiskHistory>,
+    load_history: LoadHistory,
+    pressure_history: [PressureHistory; 3],
+    sensor_history: BTreeMap<String, RingBuffer<MetricPoint>>,
+    discharge_history: BTreeMap<String, RingBuffer<MetricPoint>>,
+    vm_history: VmHistory,
+    gaps: RingBuffer<HistoryGap>,
+    history: TieredHistory,
+    completed_rollups: Vec<(HistorySeries, RollupPoint)>,
+    last_snapshot: Option<MetricsSnapshot>,
 }
 
 impl SystemCollector {
-    pub fn new(interval_ms: u64) -> Self {
-        let cap = ((60_000 / interval_ms.max(500)) as usize).max(30);
+    pub fn new() -> Self {
+        let cap = LIVE_CAP;
         Self {
-            interval_ms,
+            history_cap: cap,
+            last_time: None,
             last_cpu: None,
-            last_net: None,
-            last_disk: None,
-            cpu_history: RingBuffer::new(cap),
-            ram_history: RingBuffer::new(cap),
-            disk_read_history: RingBuffer::new(cap),
-            disk_write_history: RingBuffer::new(cap),
-            net_rx_history: RingBuffer::new(cap),
-            net_tx_history: RingBuffer::new(cap),
+            last_cores: HashMap::new(),
+            last_interfaces: HashMap::new(),
+            last_interface_indexes: HashMap::new(),
+            last_disks: HashMap::new(),
+            last_stat_counters: None,
+            last_pressure: None,
+            last_vmstat: None,
+            cpu_mode_history: CpuModes::from_fn(|| RingBuffer::new(cap)),
+            core_history: BTreeMap::new(),
+            interface_history: BTreeMap::new(),
+            disk_history: BTreeMap::new(),
+            load_history: LoadHistory::new(cap),
+            pressure_history: [
+                PressureHistory::new(cap),
+                PressureHistory::new(cap),
+                PressureHistory::new(cap),
+            ],
+            sensor_history: BTreeMap::new(),
+            discharge_history: BTreeMap::new(),
+            vm_history: VmHistory::new(cap),
+            gaps: RingBuffer::new(MAX_GAPS),
+            history: TieredHistory::new(RetentionConfig::default()),
+            completed_rollups: Vec::new(),
+            last_snapshot: None,
         }
     }
 
-    pub fn new_for_tests(interval_ms: u64) -> Self {
-        Self::new(interval_ms)
+    pub fn new_for_tests() -> Self {
+        Self::new()
+    }
+
+    pub fn history(&self) -> &TieredHistory {
+        &self.history
+    }
+
+    pub fn set_retention(&mut self, retention: RetentionConfig) {
+        self.history.set_retention(retention);
+    }
+
+    pub fn take_completed_rollups(&mut self) -> Vec<(HistorySeries, RollupPoint)> {
+        std::mem::take(&mut self.completed_rollups)
     }
 
     pub fn snapshot_len(&self) -> usize {
-        self.cpu_history.snapshot().len()
+        self.history.raw_after(HistorySeries::Cpu, i64::MIN).len()
+    }
+
+    fn ingest_cores(&mut self, cpu_raw: &str, cpufreq: &[CpuFreqRaw], now_ms: u64) -> Vec<CoreUsage> {
+        let cores = parse_core_totals(cpu_raw);
+        let freqs: Vec<_> = cpufreq.iter().filter_map(parse_cpufreq).collect();
+
+        // Offline or unplugged cores vanish from /proc/stat; forget them so a
+        // core coming back online starts from a fresh baseline.
+        self.last_cores
+            .retain(|cpu, _| cores.iter().any(|core| core.cpu == *cpu));
+        self.core_history
+            .retain(|cpu, _| cores.iter().any(|core| core.cpu == *cpu));
+
+        let mut usage = Vec::with_capacity(cores.len());
+        for core in cores {
+            let next = core.totals;
+            let (percent, modes) = self
+                .last_cores
+                .insert(core.cpu, next)
+                .map_or((0.0, CpuModes::default()), |prev| {
+                    (
+                        cpu_percent((prev.idle, prev.total), (next.idle, next.total)),
+                        cpu_mode_percent(&prev, &next),
+                    )
+                });
+
+            let cap = self.history_cap;
+            let history = self
+                .core_history
+                .entry(core.cpu)
+                .or_insert_with(|| CoreHistory::new(cap));
+            history.usage.push(MetricPoint {
+                ts_ms: now_ms as i64,
+                value: percent as f64,
+            });
+            push_modes(&mut history.modes, &modes, now_ms as i64);
+
+            let frequency = freqs.iter().find(|freq| freq.cpu == core.cpu).map(|freq| {
+                history.freq.push(MetricPoint {
+                    ts_ms: now_ms as i64,
+                    value: freq.cur_khz as f64,
+                });
+                CoreFrequency {
+                    cur_khz: freq.cur_khz,
+                    min_khz: freq.min_khz,
+                    max_khz: freq.max_khz,
+                    governor: freq.governor.clone(),
+                    energy_performance_preference: freq.energy_performance_preference.clone(),
+                    history: Vec::new(),
+                }
+            });
+
+            usage.push(CoreUsage {
+                cpu: core.cpu,
+                percent,
+                history: Vec::new(),
+                modes,
+                mode_history: CpuModes::default(),
+                frequency,
+            });
+        }
+        usage
+    }
+
+    fn ingest_interfaces(
+        &mut self,
+        net_raw: &str,
+        indexes: &HashMap<String, u32>,
+        delta_ms: f64,
+        now_ms: u64,
+    ) -> Vec<NetInterfaceUsage> {
+        let interfaces = parse_net_dev(net_raw);
+
+        // VPNs, bridges and tethered devices come and go; only interfaces seen in
+        // both samples produce rates, so a new one starts at zero instead of
+        // reporting its whole lifetime counters as a single spike.
+        self.interface_history
+            .retain(|name, _| interfaces.iter().any(|iface| &iface.name == name));
+        let mut previous = std::mem::take(&mut self.last_interfaces);
+        let previous_indexes = std::mem::replace(&mut self.last_interface_indexes, indexes.clone());
+
+        // A VPN or container veth torn down and brought back under the same name
+        // is a new device: it gets a new ifindex (or, without sysfs, its byte
+        // counters go backwards), so drop the old baseline and history.
+        for iface in &interfaces {
+            let index_changed = matches!(
+                (previous_indexes.get(&iface.name), indexes.get(&iface.name)),
+                (Some(before), Some(after)) if before != after
+            );
+            let went_backwards = previous
+                .get(&iface.name)
+                .is_some_and(|prev| iface.rx_bytes < prev.rx_bytes || iface.tx_bytes < prev.tx_bytes);
+            if index_changed || went_backwards {
+                previous.remove(&iface.name);
+                self.interface_history.remove(&iface.name);
+            }
+        }
+
+        let mut usage = Vec::with_capacity(interfaces.len());
+        for iface in interfaces {
+            let rate = |pick: fn(&NetDevCounters) -> u64| {
+                previous
+                    .get(&iface.name)
+                    .map_or(0.0, |prev| rate_per_sec(pick(prev), pick(&iface), delta_ms))
+            };
+            let rx_bps = rate(|c| c.rx_bytes);
+            let tx_bps = rate(|c| c.tx_bytes);
+            let rx_pps = rate(|c| c.rx_packets);
+            let tx_pps = rate(|c| c.tx_packets);
+            let rx_errors_ps = rate(|c| c.rx_errs);
+            let tx_errors_ps = rate(|c| c.tx_errs);
+            let rx_drops_ps = rate(|c| c.rx_drop);
+            let tx_drops_ps = rate(|c| c.tx_drop);
+            let rx_fifo_ps = rate(|c| c.rx_fifo);
+            let tx_fifo_ps = rate(|c| c.tx_fifo);
+
+            let cap = self.history_cap;
+            let history = self
+                .interface_history
+                .entry(iface.name.clone())
+                .or_insert_with(|| InterfaceHistory {
+                    rx: RingBuffer::new(cap),
+                    tx: RingBuffer::new(cap),
+                });
+            history.rx.push(MetricPoint {
+                ts_ms: now_ms as i64,
+                value: rx_bps,
+            });
+            history.tx.push(MetricPoint {
+                ts_ms: now_ms as i64,
+                value: tx_bps,
+            });
+
+            usage.push(NetInterfaceUsage {
+                name: iface.name.clone(),
+                rx_bps,
+                tx_bps,
+                rx_pps,
+                tx_pps,
+                rx_errors_ps,
+                tx_errors_ps,
+                rx_drops_ps,
+                tx_drops_ps,
+                rx_fifo_ps,
+                tx_fifo_ps,
+                rx_history: Vec::new(),
+                tx_history: Vec::new(),
+            });
+            self.last_interfaces.insert(iface.name.clone(), iface);
+        }
+        usage
+    }
+
+    fn ingest_disks(
+        &mut self,
+        disk_raw: &str,
+        block_devices: &[BlockDevice],
+        delta_ms: f64,
+        now_ms: u64,
+    ) -> Vec<DiskUsage> {
+        let disks = parse_diskstats(disk_raw);
+        let names: Vec<&str> = disks.iter().map(|disk| disk.name.as_str()).collect();
+
+        self.disk_history
+            .retain(|name, _| names.contains(&name.as_str()));
+        let previous = std::mem::take(&mut self.last_disks);
+
+        let mut usage = Vec::with_capacity(disks.len());
+        for disk in &disks {
+            let device = block_devices
+                .iter()
+                .find(|device| device.name == disk.name)
+                .cloned()
+                .unwrap_or_else(|| guess_block_device(&disk.name, &names));
+
+            let prev = previous.get(&disk.name);
+            let rate = |pick: fn(&DiskCounters) -> u64| {
+                prev.map_or(0.0, |prev| rate_per_sec(pick(prev), pick(disk), delta_ms))
+            };
+            let read_bps = rate(|c| c.read_sectors) * 512.0;
+            let write_bps = rate(|c| c.write_sectors) * 512.0;
+            let read_iops = rate(|c| c.reads);
+            let write_iops = rate(|c| c.writes);
+            let (busy_percent, avg_await_ms) = prev.map_or((0.0, 0.0), |prev| {
+                let busy_ms = counter_delta(prev.io_ticks_ms, disk.io_ticks_ms) as f64;
+                let ios = counter_delta(prev.reads + prev.writes, disk.reads + disk.writes);
+                let wait_ms = counter_delta(prev.read_ms + prev.write_ms, disk.read_ms + disk.write_ms);
+                (
+                    (busy_ms / delta_ms * 100.0).min(100.0),
+                    if ios > 0 { wait_ms as f64 / ios as f64 } else { 0.0 },
+                )
+            });
+
+            let cap = self.history_cap;
+            let history = self
+                .disk_history
+                .entry(disk.name.clone())
+                .or_insert_with(|| DiskHistory {
+                    read: RingBuffer::new(cap),
+                    write: RingBuffer::new(cap),
+                    busy: RingBuffer::new(cap),
+                });
+            let ts_ms = now_ms as i64;
+            history.read.push(MetricPoint { ts_ms, value: read_bps });
+            history.write.push(MetricPoint { ts_ms, value: write_bps });
+            history.busy.push(MetricPoint { ts_ms, value: busy_percent });
+
+            usage.push(DiskUsage {
+                name: disk.name.clone(),
+                kind: device.kind,
+                label: device.label,
+                parent: device.parent,
+                read_bps,
+                write_bps,
+                read_iops,
+                write_iops,
+                busy_percent,
+                avg_await_ms,
+                in_flight: disk.in_flight,
+                read_history: Vec::new(),
+                write_history: Vec::new(),
+                busy_history: Vec::new(),
+            });
+        }
+
+        self.last_disks = disks.into_iter().map(|disk| (disk.name.clone(), disk)).collect();
+        usage
+    }
+
+    fn ingest_load(&mut self, stat_raw: &str, loadavg_raw: &str, delta_ms: f64, now_ms: u64) -> LoadUsage {
+        let load = parse_loadavg(loadavg_raw).unwrap_or_default();
+        let counters = parse_stat_counters(stat_raw);
+        let prev = self.last_stat_counters.replace(counters);
+        let rate = |pick: fn(&StatCounters) -> u64| prev.map_or(0.0, |prev| rate_per_sec(pick(&prev), pick(&counters), delta_ms));
+        let forks_per_sec = rate(|c| c.processes);
+        let ctxt_per_sec = rate(|c| c.ctxt);
+        let intr_per_sec = rate(|c| c.intr);
+        let softirq_per_sec = rate(|c| c.softirq);
+
+        let ts_ms = now_ms as i64;
+        let history = &mut self.load_history;
+        history.load1.push(MetricPoint { ts_ms, value: load.one });
+        history.load5.push(MetricPoint { ts_ms, value: load.five });
+        history.load15.push(MetricPoint { ts_ms, value: load.fifteen });
+        history.procs_running.push(MetricPoint {
+            ts_ms,
+            value: counters.procs_running as f64,
+        });
+        history.procs_blocked.push(MetricPoint {
+            ts_ms,
+            value: counters.procs_blocked as f64,
+        });
+        history.forks.push(MetricPoint {
+            ts_ms,
+            value: forks_per_sec,
+        });
+        history.ctxt.push(MetricPoint {
+            ts_ms,
+            value: ctxt_per_sec,
+        });
+        history.intr.push(MetricPoint {
+            ts_ms,
+            value: intr_per_sec,
+        });
+        history.softirq.push(MetricPoint {
+            ts_ms,
+            value: softirq_per_sec,
+        });
+
+        LoadUsage {
+            load1: load.one,
+            load5: load.five,
+            load15: load.fifteen,
+            runnable_tasks: load.runnable,
+            total_tasks: load.total_tasks,
+            procs_running: counters.procs_running,
+            procs_blocked: counters.procs_blocked,
+            forks_per_sec,
+            ctxt_per_sec,
+            intr_per_sec,
+            softirq_per_sec,
+            load1_history: Vec::new(),
+            load5_history: Vec::new(),
+            load15_history: Vec::new(),
+            procs_running_history: Vec::new(),
+            procs_blocked_history: Vec::new(),
+            forks_history: Vec::new(),
+            ctxt_history: Vec::new(),
+            intr_history: Vec::new(),
+            softirq_history: Vec::new(),
+        }
     }
 
-    fn parse_network_bytes(line: &str) -> Option<(u64, u64)> {
-        let mut parts = line.split_whitespace();
-        parts.next()?;
-        let rx = parts.next()?.parse::<u64>().ok()?;
-        let skip_len = 7;
-        for _ in 0..skip_len {
-            parts.next()?;
+    fn ingest_pressure(&mut self, sample: &ProcSample, delta_ms: f64, now_ms: u64) -> PressureUsage {
+        let parse = |raw: &Option<String>| raw.as_deref().and_then(parse_pressure);
+        // Kernels built without CONFIG_PSI (or booted with psi=0) have no usable
+        // /proc/pressure files; report that instead of failing the snapshot.
+        let (Some(cpu), Some(memory), Some(io)) = (
+            parse(&sample.pressure_cpu),
+            parse(&sample.pressure_memory),
+            parse(&sample.pressure_io),
+        ) else {
+            self.last_pressure = None;
+            return PressureUsage::default();
+        };
+        let next = [cpu, memory, io];
+
+        let prev = self.last_pressure.replace(next);
+        let ts_ms = now_ms as i64;
+        let [cpu_history, memory_history, io_history] = &mut self.pressure_history;
+        PressureUsage {
+            supported: true,
+            cpu: pressure_stats(cpu_history, prev.as_ref().map(|p| &p[0]), &next[0], delta_ms, ts_ms),
+            memory: pressure_stats(memory_history, prev.as_ref().map(|p| &p[1]), &next[1], delta_ms, ts_ms),
+            io: pressure_stats(io_history, prev.as_ref().map(|p| &p[2]), &next[2], delta_ms, ts_ms),
         }
-        let tx = parts.next()?.parse::<u64>().ok()?;
-        Some((rx, tx))
     }
 
-    fn parse_disk_bytes(line: &str) -> Option<(u64, u64)> {
-        let mut parts = line.split_whitespace().skip(2);
-        parts.next()?;
-        let read = parts.next()?.parse::<u64>().ok()?;
-        let _ = parts.next()?;
-        let _ = parts.next()?;
-        let _ = parts.next()?;
-        let write = parts.next()?.parse::<u64>().ok()?;
-        Some((read, write))
+    fn ingest_sensors(&mut self, readings: &[SensorReading], now_ms: u64) -> Vec<SensorUsage> {
+        self.sensor_history
+            .retain(|id, _| readings.iter().any(|reading| &reading.id == id));
+
+        readings
+            .iter()
+            .map(|reading| {
+                let cap = self.history_cap;
+                let history = self
+                    .sensor_history
+                    .entry(reading.id.clone())
+                    .or_insert_with(|| RingBuffer::new(cap));
+                history.push(MetricPoint {
+                    ts_ms: now_ms as i64,
+                    value: reading.value,
+                });
+
+                SensorUsage {
+                    id: reading.id.clone(),
+                    chip: reading.chip.clone(),
+                    label: reading.label.clone(),
+                    kind: reading.kind,
+                    value: reading.value,
+                    max: reading.max,
+                    crit: reading.crit,
+                    history: Vec::new(),
+                }
+            })
+            .collect()
+    }
+
+    fn ingest_power(&mut self, uevents: &[String], now_ms: u64) -> PowerUsage {
+        let supplies: Vec<_> = uevents
+            .iter()
+            .filter_map(|raw| parse_power_supply_uevent(raw))
+            .collect();
+        let ac_online = supplies
+            .iter()
+            .filter(|supply| supply.is_mains())
+            .find_map(|supply| supply.online);
+
+        let batteries: Vec<_> = supplies.iter().filter(|supply| supply.is_battery()).collect();
+        self.discharge_history
+            .retain(|name, _| batteries.iter().any(|battery| &battery.name == name));
+
+        let to_wh = |uwh: Option<f64>| uwh.map(|value| value / 1_000_000.0);
+        let batteries = batteries
+            .into_iter()
+            .map(|battery| {
+                let status = battery.status.clone().unwrap_or_else(|| "Unknown".to_string());
+                let discharging = status == "Discharging";
+                let power_now_w = battery.power_now_uw.map(|uw| uw / 1_000_000.0);
+                let energy_now_wh = to_wh(battery.energy_now_uwh);
+                let energy_full_wh = to_wh(battery.energy_full_uwh);
+                let energy_full_design_wh = to_wh(battery.energy_full_design_uwh);
+                let wear_percent = match (energy_full_wh, energy_full_design_wh) {
+                    (Some(full), Some(design)) if design > 0.0 => Some((1.0 - full / design) * 100.0),
+                    _ => None,
+                };
+                let time_to_empty_secs = match (energy_now_wh, power_now_w) {
+                    (Some(energy), Some(power)) if discharging && power > 0.0 => {
+                        Some((energy / power * 3600.0) as u64)
+                    }
+                    _ => None,
+                };
+
+                let cap = self.history_cap;
+                let history = self
+                    .discharge_history
+                    .entry(battery.name.clone())
+                    .or_insert_with(|| RingBuffer::new(cap));
+                history.push(MetricPoint {
+                    ts_ms: now_ms as i64,
+                    value: if discharging { power_now_w.unwrap_or(0.0) } else { 0.0 },
+                });
+
+                BatteryUsage {
+                    name: battery.name.clone(),
+                    status,
+                    capacity_percent: battery.capacity_percent,
+                    energy_now_wh,
+                    energy_full_wh,
+                    energy_full_design_wh,
+                    wear_percent,
+                    power_now_w,
+                    time_to_empty_secs,
+                    discharge_history: Vec::new(),
+                }
+            })
+            .collect();
+
+        PowerUsage { ac_online, batteries }
+    }
+
+    fn ingest_vmstat(&mut self, vmstat_raw: &str, delta_ms: f64, now_ms: u64) -> VmActivity {
+        if vmstat_raw.is_empty() {
+            return VmActivity::default();
+        }
+        let next = parse_vmstat(vmstat_raw);
+        let prev = self.last_vmstat.replace(next);
+        let rate = |pick: fn(&VmStat) -> u64| prev.map_or(0.0, |prev| rate_per_sec(pick(&prev), pick(&next), delta_ms));
+        let oom_kills = prev.map_or(0, |prev| counter_delta(prev.oom_kill, next.oom_kill));
+
+        let activity = VmActivity {
+            pgfault_ps: rate(|vm| vm.pgfault),
+            pgmajfault_ps: rate(|vm| vm.pgmajfault),
+            pswpin_ps: rate(|vm| vm.pswpin),
+            pswpout_ps: rate(|vm| vm.pswpout),
+            pgscan_ps: rate(|vm| vm.pgscan),
+            pgsteal_ps: rate(|vm| vm.pgsteal),
+            compact_stall_ps: rate(|vm| vm.compact_stall),
+            oom_kill_total: next.oom_kill,
+            oom_kills,
+            ..VmActivity::default()
+        };
+
+        let ts_ms = now_ms as i64;
+        let history = &mut self.vm_history;
+        let series = [
+            (&mut history.pgfault, activity.pgfault_ps),
+            (&mut history.pgmajfault, activity.pgmajfault_ps),
+            (&mut history.pswpin, activity.pswpin_ps),
+            (&mut history.pswpout, activity.pswpout_ps),
+            (&mut history.pgscan, activity.pgscan_ps),
+            (&mut history.pgsteal, activity.pgsteal_ps),
+            (&mut history.compact_stall, activity.compact_stall_ps),
+            (&mut history.oom_kill, oom_kills as f64),
+        ];
+        for (buffer, value) in series {
+            buffer.push(MetricPoint { ts_ms, value });
+        }
+        activity
     }
 
     pub fn ingest_for_tests(
@@ -74,76 +781,97 @@ impl SystemCollector {
         disk_raw: &str,
         now_ms: u64,
     ) -> Option<MetricsSnapshot> {
-        let cpu_totals = parse_cpu_totals(cpu_raw)?;
-        let mem = parse_meminfo(mem_raw)?;
-        let net = Self::parse_network_bytes(net_raw)?;
-        let disk = Self::parse_disk_bytes(disk_raw)?;
+        let sample = ProcSample {
+            stat: cpu_raw.to_string(),
+            meminfo: mem_raw.to_string(),
+            net_dev: net_raw.to_string(),
+            diskstats: disk_raw.to_string(),
+            ..ProcSample::default()
+        };
+        self.ingest(&sample, SampleTime::at(now_ms))
+    }
 
-        let cpu_percent = self
+    pub fn ingest(&mut self, sample: &ProcSample, time: SampleTime) -> Option<MetricsSnapshot> {
+        let now_ms = time.wall_ms;
+        let cpu_totals = parse_cpu_totals(&sample.stat)?;
+        let mem = parse_meminfo(&sample.meminfo)?;
+
+        let cpu_percent = self.last_cpu.map_or(0.0, |prev| {
+            cpu_percent((prev.idle, prev.total), (cpu_totals.idle, cpu_totals.total)) as f64
+        });
+        let cpu_modes = self
             .last_cpu
-            .map_or(0.0, |prev| cpu_percent(prev, (cpu_totals.idle, cpu_totals.total)) as f64);
+            .map_or(CpuModes::default(), |prev| cpu_mode_percent(&prev, &cpu_totals));
 
-        let (net_rx_bps, net_tx_bps) = self
-            .last_net
-            .map(|prev| {
-                let delta_ms = self.interval_ms.max(1) as f64;
-                (
-                    ((net.0.saturating_sub(prev.0) as f64) * 1000.0) / delta_ms,
-                    ((net.1.saturating_sub(prev.1) as f64) * 1000.0) / delta_ms,
-                )
-            })
-            .unwrap_or((0.0, 0.0));
+        // Counters stand still while the machine is suspended, so rates are taken
+        // over running time only and the suspended stretch is recorded as a gap
+        // for charts to break the line at instead of drawing across it.
+        let elapsed = self.last_time.map(|prev| (prev, time.since(&prev)));
+        if let Some((prev, elapsed)) = elapsed {
+            if elapsed.suspended_ms >= SUSPEND_GAP_MIN_MS {
+                self.gaps.push(HistoryGap {
+                    start_ms: prev.wall_ms as i64,
+                    end_ms: now_ms as i64,
+                });
+            }
+        }
+        let delta_ms = elapsed.map_or(0, |(_, elapsed)| elapsed.running_ms).max(1) as f64;
 
-        let (disk_read_bps, disk_write_bps) = self
-            .last_disk
-            .map(|prev| {
-                let delta_ms = self.interval_ms.max(1) as f64;
-                (
-                    ((disk.0.saturating_sub(prev.0) as f64) * 1000.0) / delta_ms,
-                    ((disk.1.saturating_sub(prev.1) as f64) * 1000.0) / delta_ms,
-                )
-            })
-            .unwrap_or((0.0, 0.0));
+        let net_interfaces = self.ingest_interfaces(&sample.net_dev, &sample.interface_indexes, delta_ms, now_ms);
+        let net_rx_bps = net_interfaces.iter().map(|iface| iface.rx_bps).sum();
+        let net_tx_bps = net_interfaces.iter().map(|iface| iface.tx_bps).sum();
+
+        let disks = self.ingest_disks(&sample.diskstats, &sample.block_devices, delta_ms, now_ms);
+        // Only whole physical disks count towards the totals; partitions and
+        // dm volumes would otherwise count the same I/O twice, and optical
+        // drives are left out as they always have been.
+        let whole_disks = || disks.iter().filter(|disk| disk.kind == BlockDeviceKind::Disk);
+        let disk_read_bps = whole_disks().map(|disk| disk.read_bps).sum();
+        let disk_write_bps = whole_disks().map(|disk| disk.write_bps).sum();
+
+        let cores = self.ingest_cores(&sample.stat, &sample.cpufreq, now_ms);
+        let load = self.ingest_load(&sample.stat, &sample.loadavg, delta_ms, now_ms);
+        let pressure = self.ingest_pressure(sample, delta_ms, now_ms);
+        let sensors = self.ingest_sensors(&sample.sensors, now_ms);
+        let power = self.ingest_power(&sample.power_supplies, now_ms);
+        let memory = memory_breakdown(&sample.meminfo);
+        let vm = self.ingest_vmstat(&sample.vmstat, delta_ms, now_ms);
 
         let ram_used_bytes = (mem.mem_total_kb.saturating_sub(mem.mem_available_kb)) * 1024;
         let ram_total_bytes = mem.mem_total_kb * 1024;
         let swap_used_bytes = (mem.swap_total_kb.saturating_sub(mem.swap_free_kb)) * 1024;
         let swap_total_bytes = mem.swap_total_kb * 1024;
 
-        self.cpu_history.push(MetricPoint {
-            ts_ms: now_ms as i64,
-            value: cpu_percent,
-        });
-        self.ram_history.push(MetricPoint {
-            ts_ms: now_ms as i64,
-            value: if ram_total_bytes > 0 {
-                ram_used_bytes as f64 / ram_total_bytes as f64 * 100.0
-            } else {
-                0.0
-            },
-        });
-        self.disk_read_history.push(MetricPoint {
-            ts_ms: now_ms as i64,
-            value: disk_read_bps,
-        });
-        self.disk_write_history.push(MetricPoint {
-            ts_ms: now_ms as i64,
-            value: disk_write_bps,
-        });
-        self.net_rx_history.push(MetricPoint {
-            ts_ms: now_ms as i64,
-            value: net_rx_bps,
-        });
-        self.net_tx_history.push(MetricPoint {
-            ts_ms: now_ms as i64,
-            value: net_tx_bps,
-        });
+        let ram_percent = if ram_total_bytes > 0 {
+            ram_used_bytes as f64 / ram_total_bytes as f64 * 100.0
+        } else {
+            0.0
+        };
 
-        self.last_cpu = Some((cpu_totals.idle, cpu_totals.total));
-        self.last_net = Some(net);
-        self.last_disk = Some(disk);
+        push_modes(&mut self.cpu_mode_history, &cpu_modes, now_ms as i64);
+
+        for (series, value) in [
+            (HistorySeries::Cpu, cpu_percent),
+            (HistorySeries::Ram, ram_percent),
+            (HistorySeries::DiskRead, disk_read_bps),
+            (HistorySeries::DiskWrite, disk_write_bps),
+            (HistorySeries::NetRx, net_rx_bps),
+            (HistorySeries::NetTx, net_tx_bps),
+        ] {
+            let point = MetricPoint {
+                ts_ms: now_ms as i64,
+                value,
+            };
+            if let Some(rollup) = self.history.push(series, point) {
+                self.completed_rollups.push((series, rollup));
+            }
+        }
 
-        Some(MetricsSnapshot {
+        let previous_ms = self.last_time.replace(time).map(|prev| prev.wall_ms as i64);
+        self.last_cpu = Some(cpu_totals);
+
+        self.last_snapshot = Some(MetricsSnapshot {
+            ts_ms: now_ms as i64,
             cpu_percent: cpu_percent as f32,
             ram_used_bytes,
             ram_total_bytes,
@@ -153,23 +881,236 @@ impl SystemCollector {
             disk_write_bps,
             net_rx_bps,
             net_tx_bps,
-            cpu_history: self.cpu_history.snapshot(),
-            ram_history: self.ram_history.snapshot(),
-            disk_read_history: self.disk_read_history.snapshot(),
-            disk_write_history: self.disk_write_history.snapshot(),
-            net_rx_history: self.net_rx_history.snapshot(),
-            net_tx_history: self.net_tx_history.snapshot(),
-        })
+            cpu_history: Vec::new(),
+            ram_history: Vec::new(),
+            disk_read_history: Vec::new(),
+            disk_write_history: Vec::new(),
+            net_rx_history: Vec::new(),
+            net_tx_history: Vec::new(),
+            live_window_ms: LIVE_WINDOW_MS,
+            cpu_modes,
+            cpu_mode_history: CpuModes::default(),
+            cores,
+            net_interfaces,
+            disks,
+            load,
+            pressure,
+            sensors,
+            power,
+            memory,
+            vm,
+            gaps: Vec::new(),
+        });
+        self.snapshot_since(previous_ms)
+    }
+
+    // Per-device series are live only (see LIVE_WINDOW_MS). They are
+    // addressed as `core/0`, `core/0/freq`, `net/eth0/rx`, `disk/sda/busy`,
+    // `sensor/hwmon0/temp1` or `battery/BAT0`.
+    pub fn device_series(&self) -> Vec<String> {
+        let mut names = Vec::new();
+        for (cpu, history) in &self.core_history {
+            names.push(format!("core/{cpu}"));
+            if history.freq.first().is_some() {
+                names.push(format!("core/{cpu}/freq"));
+            }
+        }
+        for name in self.interface_history.keys() {
+            names.extend(["rx", "tx"].map(|dir| format!("net/{name}/{dir}")));
+        }
+        for name in self.disk_history.keys() {
+            names.extend(["read", "write", "busy"].map(|kind| format!("disk/{name}/{kind}")));
+        }
+        names.extend(self.sensor_history.keys().map(|id| format!("sensor/{id}")));
+        names.extend(self.discharge_history.keys().map(|name| format!("battery/{name}")));
+        names
+    }
+
+    pub fn has_device_series(&self, key: &str) -> bool {
+        self.device_history(key).is_some()
+    }
+
+    // Copies out the live points of one device series within the range.
+    pub fn device_points(&self, key: &str, start_ms: i64, end_ms: i64) -> Option<Vec<RollupPoint>> {
+        let live_start_ms = self.live_start_ms();
+        let buffer = self.device_history(key)?;
+        Some(
+            buffer
+                .iter()
+                .filter(|point| point.ts_ms > live_start_ms && point.ts_ms >= start_ms && point.ts_ms <= end_ms)
+                .map(RollupPoint::from)
+                .collect(),
+        )
+    }
+
+    fn device_history(&self, key: &str) -> Option<&RingBuffer<MetricPoint>> {
+        let (kind, rest) = key.split_once('/')?;
+        match kind {
+            "core" => match rest.split_once('/') {
+                None => Some(&self.core_history.get(&rest.parse().ok()?)?.usage),
+                Some((cpu, "freq")) => Some(&self.core_history.get(&cpu.parse().ok()?)?.freq),
+                Some(_) => None,
+            },
+            "net" => {
+                let (name, dir) = rest.rsplit_once('/')?;
+                let history = self.interface_history.get(name)?;
+                match dir {
+                    "rx" => Some(&history.rx),
+                    "tx" => Some(&history.tx),
+                    _ => None,
+                }
+            }
+            "disk" => {
+                let (name, series) = rest.rsplit_once('/')?;
+                let history = self.disk_history.get(name)?;
+                match series {
+                    "read" => Some(&history.read),
+                    "write" => Some(&history.write),
+                    "busy" => Some(&history.busy),
+                    _ => None,
+                }
+            }
+            "sensor" => self.sensor_history.get(rest),
+            "battery" => self.discharge_history.get(rest),
+            _ => None,
+        }
+    }
+
+    pub fn latest_ts_ms(&self) -> Option<i64> {
+        self.last_snapshot.as_ref().map(|snapshot| snapshot.ts_ms)
+    }
+
+    // Points at or before this are outside the live window.
+    fn live_start_ms(&self) -> i64 {
+        self.latest_ts_ms().map_or(i64::MIN, |latest| latest - LIVE_WINDOW_MS)
+    }
+
+    // Full history, for a client (re)building its charts from scratch.
+    pub fn bootstrap(&self) -> Option<MetricsSnapshot> {
+        self.snapshot_with_history(i64::MIN)
+    }
+
+    // Only the points newer than `since_ms`; without a cursor, just the points
+    // of the latest sample.
+    pub fn snapshot_since(&self, since_ms: Option<i64>) -> Option<MetricsSnapshot> {
+        let latest_ms = self.latest_ts_ms()?;
+        self.snapshot_with_history(since_ms.unwrap_or(latest_ms - 1))
+    }
+
+    fn snapshot_with_history(&self, since_ms: i64) -> Option<MetricsSnapshot> {
+        let mut snapshot = self.last_snapshot.clone()?;
+        self.fill_history(&mut snapshot, since_ms);
+        Some(snapshot)
+    }
+
+    // Every history vector in a snapshot is filled here and nowhere else, so
+    // an incremental poll costs the new points rather than the whole window.
+    fn fill_history(&self, snapshot: &mut MetricsSnapshot, since_ms: i64) {
+        let since_ms = since_ms.max(self.live_start_ms());
+        let after = |buffer: &RingBuffer<MetricPoint>| buffer.tail_while(|point| point.ts_ms > since_ms);
+        let aggregate = |series| self.history.raw_after(series, since_ms);
+
+        snapshot.cpu_history = aggregate(HistorySeries::Cpu);
+        snapshot.ram_history = aggregate(HistorySeries::Ram);
+        snapshot.disk_read_history = aggregate(HistorySeries::DiskRead);
+        snapshot.disk_write_history = aggregate(HistorySeries::DiskWrite);
+        snapshot.net_rx_history = aggregate(HistorySeries::NetRx);
+        snapshot.net_tx_history = aggregate(HistorySeries::NetTx);
+        snapshot.cpu_mode_history = self.cpu_mode_history.map(|buffer| after(buffer));
+
+        for core in &mut snapshot.cores {
+            let Some(history) = self.core_history.get(&core.cpu) else {
+                continue;
+            };
+            core.history = after(&history.usage);
+            core.mode_history = history.modes.map(|buffer| after(buffer));
+            if let Some(frequency) = core.frequency.as_mut() {
+                frequency.history = after(&history.freq);
+            }
+        }
+        for iface in &mut snapshot.net_interfaces {
+            if let Some(history) = self.interface_history.get(&iface.name) {
+                iface.rx_history = after(&history.rx);
+                iface.tx_history = after(&history.tx);
+            }
+        }
+        for disk in &mut snapshot.disks {
+            if let Some(history) = self.disk_history.get(&disk.name) {
+                disk.read_history = after(&history.read);
+                disk.write_history = after(&history.write);
+                disk.busy_history = after(&history.busy);
+            }
+        }
+
+        let load = &mut snapshot.load;
+        let history = &self.load_history;
+        load.load1_history = after(&history.load1);
+        load.load5_history = after(&history.load5);
+        load.load15_history = after(&history.load15);
+        load.procs_running_history = after(&history.procs_running);
+        load.procs_blocked_history = after(&history.procs_blocked);
+        load.forks_history = after(&history.forks);
+        load.ctxt_history = after(&history.ctxt);
+        load.intr_history = after(&history.intr);
+        load.softirq_history = after(&history.softirq);
+
+        if snapshot.pressure.supported {
+            let pressure = &mut snapshot.pressure;
+            for (stats, history) in [&mut pressure.cpu, &mut pressure.memory, &mut pressure.io]
+                .into_iter()
+                .zip(&self.pressure_history)
+            {
+                stats.some_history = after(&history.some);
+                stats.full_history = after(&history.full);
+            }
+        }
+        for sensor in &mut snapshot.sensors {
+            if let Some(history) = self.sensor_history.get(&sensor.id) {
+                sensor.history = after(history);
+            }
+        }
+        for battery in &mut snapshot.power.batteries {
+            if let Some(history) = self.discharge_history.get(&battery.name) {
+                battery.discharge_history = after(history);
+            }
+        }
+
+        if self.last_vmstat.is_some() {
+            let vm = &mut snapshot.vm;
+            let history = &self.vm_history;
+            vm.pgfault_history = after(&history.pgfault);
+            vm.pgmajfault_history = after(&history.pgmajfault);
+            vm.pswpin_history = after(&history.pswpin);
+            vm.pswpout_history = after(&history.pswpout);
+            vm.pgscan_history = after(&history.pgscan);
+            vm.pgsteal_history = after(&history.pgsteal);
+            vm.compact_stall_history = after(&history.compact_stall);
+            vm.oom_kill_history = after(&history.oom_kill);
+        }
+
+        snapshot.gaps = self
+            .gaps
+            .iter()
+            .filter(|gap| gap.end_ms > since_ms)
+            .copied()
+            .collect();
+    }
+}
+
+impl Default for SystemCollector {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::SystemCollector;
+    use crate::system::clock::SampleTime;
 
     #[test]
     fn collector_generates_rates_and_history_points() {
-        let mut collector = SystemCollector::new_for_tests(500);
+        let mut collector = SystemCollector::new_for_tests();
         let _ = collector.ingest_for_tests(
             "cpu  1 1 1 10 0 0 0 0 0 0\n",
             "MemTotal: 1000 kB\nMemAvailable: 400 kB\nSwapTotal: 200 kB\nSwapFree: 100 kB\n",
@@ -189,6 +1130,391 @@ mod tests {
             .unwrap();
         assert!(snap.net_rx_bps > 0.0);
         assert!(snap.disk_write_bps > 0.0);
-        assert_eq!(snap.cpu_history.len(), 2);
+        assert_eq!(snap.cpu_history.len(), 1);
+        assert_eq!(collector.bootstrap().unwrap().cpu_history.len(), 2);
+    }
+
+    #[test]
+    fn snapshots_and_device_series_stay_within_the_live_window() {
+        let mem = "MemTotal: 1000 kB\nMemAvailable: 400 kB\nSwapTotal: 0 kB\nSwapFree: 0 kB\n";
+        let net = "eth0: 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0\n";
+        let mut collector = SystemCollector::new_for_tests();
+        for second in 1..=90_u64 {
+            let stat = format!("cpu  {second} 0 0 100 0 0 0 0 0 0\ncpu0 {second} 0 0 100 0 0 0 0 0 0\n");
+            collector.ingest_for_tests(&stat, mem, net, "", second * 1_000);
+        }
+
+        // 90 samples went in; the snapshot only reaches back a minute, while
+        // the tiered history still holds every aggregate point.
+        let full = collector.bootstrap().unwrap();
+        assert_eq!(full.live_window_ms, super::LIVE_WINDOW_MS);
+        assert_eq!(full.cpu_history.first().map(|p| p.ts_ms), Some(31_000));
+        assert_eq!(full.cpu_history.len(), 60);
+        assert_eq!(full.cores[0].history.len(), 60);
+        assert_eq!(collector.snapshot_len(), 90);
+        let core = collector.device_points("core/0", 0, i64::MAX).unwrap();
+        assert_eq!(core.first().map(|p| p.ts_ms), Some(31_000));
+        assert!(collector.device_points("core/7", 0, i64::MAX).is_none());
+    }
+
+    #[test]
+    fn collector_hands_out_only_points_after_the_cursor() {
+        let mem = "MemTotal: 1000 kB\nMemAvailable: 400 kB\nSwapTotal: 0 kB\nSwapFree: 0 kB\n";
+        let net = "eth0: 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0\n";
+        let mut collector = SystemCollector::new_for_tests();
+        assert!(collector.bootstrap().is_none());
+        for (i, ts) in [1_000, 1_500, 2_000].into_iter().enumerate() {
+            let stat = format!("cpu  {i} 0 0 100 0 0 0 0 0 0\n");
+            let snap = collector.ingest_for_tests(&stat, mem, net, "", ts).unwrap();
+            assert_eq!(snap.ts_ms, ts as i64);
+            assert_eq!(snap.cpu_history.len(), 1);
+            assert_eq!(snap.net_interfaces[0].rx_history.len(), 1);
+        }
+
+        let since = collector.snapshot_since(Some(1_000)).unwrap();
+        assert_eq!(since.cpu_history.iter().map(|p| p.ts_ms).collect::<Vec<_>>(), vec![1_500, 2_000]);
+        assert_eq!(since.net_interfaces[0].tx_history.len(), 2);
+        assert!(collector.snapshot_since(Some(2_000)).unwrap().ram_history.is_empty());
+        assert_eq!(collector.snapshot_since(None).unwrap().ram_history.len(), 1);
+        assert_eq!(collector.bootstrap().unwrap().cpu_history.len(), 3);
+    }
+
+    #[test]
+    fn collector_tracks_per_core_usage_across_hotplug() {
+        let mem = "MemTotal: 1000 kB\nMemAvailable: 400 kB\nSwapTotal: 0 kB\nSwapFree: 0 kB\n";
+        let net = "eth0: 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0\n";
+        let disk = "   8       0 sda 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0\n";
+        let mut collector = SystemCollector::new_for_tests();
+        collector
+            .ingest_for_tests(
+                "cpu  0 0 0 200 0 0 0 0 0 0\ncpu0 0 0 0 100 0 0 0 0 0 0\ncpu1 0 0 0 100 0 0 0 0 0 0\n",
+                mem,
+                net,
+                disk,
+                1_000,
+            )
+            .unwrap();
+
+        // cpu0 is pegged, cpu1 went offline and cpu2 was hotplugged.
+        let snap = collector
+            .ingest_for_tests(
+                "cpu  100 0 0 300 0 0 0 0 0 0\ncpu0 100 0 0 100 0 0 0 0 0 0\ncpu2 0 0 0 50 0 0 0 0 0 0\n",
+                mem,
+                net,
+                disk,
+                1_500,
+            )
+            .unwrap();
+
+        assert!((snap.cpu_percent - 50.0).abs() < 0.01);
+        assert_eq!(snap.cores.len(), 2);
+        assert_eq!(snap.cores[0].cpu, 0);
+        assert!((snap.cores[0].percent - 100.0).abs() < 0.01);
+        assert_eq!(collector.bootstrap().unwrap().cores[0].history.len(), 2);
+        assert_eq!(snap.cores[1].cpu, 2);
+        assert_eq!(snap.cores[1].percent, 0.0);
+        assert_eq!(snap.cores[1].history.len(), 1);
+    }
+
+    #[test]
+    fn collector_reports_mode_breakdown_for_aggregate_and_cores() {
+        let mem = "MemTotal: 1000 kB\nMemAvailable: 400 kB\nSwapTotal: 0 kB\nSwapFree: 0 kB\n";
+        let net = "eth0: 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0\n";
+        let disk = "   8       0 sda 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0\n";
+        let mut collector = SystemCollector::new_for_tests();
+        collector
+            .ingest_for_tests("cpu  0 0 0 100 0 0 0 0 0 0\ncpu0 0 0 0 100 0 0 0 0 0 0\n", mem, net, disk, 1_000)
+            .unwrap();
+        let snap = collector
+            .ingest_for_tests("cpu  0 0 10 150 40 0 0 0 0 0\ncpu0 0 0 10 150 40 0 0 0 0 0\n", mem, net, disk, 1_500)
+            .unwrap();
+
+        assert!((snap.cpu_modes.iowait - 40.0).abs() < 0.01);
+        assert!((snap.cpu_modes.system - 10.0).abs() < 0.01);
+        assert!((snap.cores[0].modes.iowait - 40.0).abs() < 0.01);
+        let full = collector.bootstrap().unwrap();
+        assert_eq!(full.cpu_mode_history.iowait.len(), 2);
+        assert_eq!(full.cores[0].mode_history.idle.len(), 2);
+    }
+
+    #[test]
+    fn collector_reports_per_interface_rates_without_hotplug_spikes() {
+        let cpu = "cpu  0 0 0 100 0 0 0 0 0 0\n";
+        let mem = "MemTotal: 1000 kB\nMemAvailable: 400 kB\nSwapTotal: 0 kB\nSwapFree: 0 kB\n";
+        let disk = "   8       0 sda 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0\n";
+        let mut collector = SystemCollector::new_for_tests();
+        collector
+            .ingest_for_tests(cpu, mem, "  eth0: 1000 10 0 0 0 0 0 0 500 5 0 0 0 0 0 0\n", disk, 1_000)
+            .unwrap();
+
+        // A docker bridge appears with large lifetime counters.
+        let snap = collector
+            .ingest_for_tests(
+                cpu,
+                mem,
+                "  eth0: 3000 30 1 2 0 0 0 0 900 9 0 0 0 0 0 0\ndocker0: 900000 9000 0 0 0 0 0 0 900000 9000 0 0 0 0 0 0\n",
+                disk,
+                2_000,
+            )
+            .unwrap();
+
+        assert_eq!(snap.net_interfaces.len(), 2);
+        let eth0 = snap.net_interfaces.iter().find(|i| i.name == "eth0").unwrap();
+        assert!((eth0.rx_bps - 2000.0).abs() < 0.01);
+        assert!((eth0.rx_pps - 20.0).abs() < 0.01);
+        assert!((eth0.rx_errors_ps - 1.0).abs() < 0.01);
+        assert!((eth0.rx_drops_ps - 2.0).abs() < 0.01);
+        let docker0 = snap.net_interfaces.iter().find(|i| i.name == "docker0").unwrap();
+        assert_eq!(docker0.rx_bps, 0.0);
+        assert!((snap.net_rx_bps - 2000.0).abs() < 0.01);
+
+        // The bridge goes away again; the aggregate must not dip below eth0's traffic.
+        let snap = collector
+            .ingest_for_tests(cpu, mem, "  eth0: 4000 40 1 2 0 0 0 0 1000 10 0 0 0 0 0 0\n", disk, 3_000)
+            .unwrap();
+        assert_eq!(snap.net_interfaces.len(), 1);
+        assert!((snap.net_rx_bps - 1000.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn collector_reports_per_disk_stats_without_partition_double_counting() {
+        let cpu = "cpu  0 0 0 100 0 0 0 0 0 0\n";
+        let mem = "MemTotal: 1000 kB\nMemAvailable: 400 kB\nSwapTotal: 0 kB\nSwapFree: 0 kB\n";
+        let net = "eth0: 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0\n";
+        let mut collector = SystemCollector::new_for_tests();
+        collector
+            .ingest_for_tests(
+                cpu,
+                mem,
+                net,
+                "   8       0 sda 100 0 1000 200 50 0 500 100 0 100 300 0 0 0 0\n   8       1 sda1 100 0 1000 200 50 0 500 100 0 100 300 0 0 0 0\n",
+                1_000,
+            )
+            .unwrap();
+        let snap = collector
+            .ingest_for_tests(
+                cpu,
+                mem,
+                net,
+                "   8       0 sda 110 0 3000 240 60 0 900 160 2 600 400 0 0 0 0\n   8       1 sda1 110 0 3000 240 60 0 900 160 2 600 400 0 0 0 0\n",
+                2_000,
+            )
+            .unwrap();
+
+        assert!((snap.disk_read_bps - 2000.0 * 512.0).abs() < 0.01);
+        let sda = snap.disks.iter().find(|d| d.name == "sda").unwrap();
+        assert!((sda.read_iops - 10.0).abs() < 0.01);
+        assert!((sda.write_iops - 10.0).abs() < 0.01);
+        assert!((sda.busy_percent - 50.0).abs() < 0.01);
+        assert!((sda.avg_await_ms - 5.0).abs() < 0.01);
+        assert_eq!(sda.in_flight, 2);
+        let sda1 = snap.disks.iter().find(|d| d.name == "sda1").unwrap();
+        assert_eq!(sda1.kind, crate::system::models::BlockDeviceKind::Partition);
+    }
+
+    #[test]
+    fn collector_reports_load_fork_and_switch_rates() {
+        let mem = "MemTotal: 1000 kB\nMemAvailable: 400 kB\nSwapTotal: 0 kB\nSwapFree: 0 kB\n";
+        let mut collector = SystemCollector::new_for_tests();
+        let mut sample = super::ProcSample {
+            stat: "cpu  0 0 0 100 0 0 0 0 0 0\nintr 10000 0\nctxt 20000\nprocesses 1000\nprocs_running 3\nprocs_blocked 1\nsoftirq 3000 0\n".into(),
+            meminfo: mem.into(),
+            loadavg: "1.50 0.75 0.25 3/400 999\n".into(),
+            ..Default::default()
+        };
+        collector.ingest(&sample, SampleTime::at(1_000)).unwrap();
+        sample.stat = "cpu  0 0 0 200 0 0 0 0 0 0\nintr 12500 0\nctxt 26000\nprocesses 1050\nprocs_running 5\nprocs_blocked 2\nsoftirq 3400 0\n".into();
+        let snap = collector.ingest(&sample, SampleTime::at(2_000)).unwrap();
+
+        assert!((snap.load.load1 - 1.5).abs() < 1e-9);
+        assert_eq!(snap.load.total_tasks, 400);
+        assert_eq!(snap.load.procs_running, 5);
+        assert_eq!(snap.load.procs_blocked, 2);
+        assert!((snap.load.forks_per_sec - 50.0).abs() < 0.01);
+        assert!((snap.load.ctxt_per_sec - 6_000.0).abs() < 0.01);
+        assert!((snap.load.intr_per_sec - 2_500.0).abs() < 0.01);
+        assert!((snap.load.softirq_per_sec - 400.0).abs() < 0.01);
+        assert_eq!(collector.bootstrap().unwrap().load.forks_history.len(), 2);
+    }
+
+    #[test]
+    fn collector_derives_stall_percent_and_flags_missing_psi() {
+        let mem = "MemTotal: 1000 kB\nMemAvailable: 400 kB\nSwapTotal: 0 kB\nSwapFree: 0 kB\n";
+        let psi = |total: u64| Some(format!("some avg10=2.00 avg60=1.00 avg300=0.50 total={total}\nfull avg10=0.00 avg60=0.00 avg300=0.00 total=0\n"));
+        let mut collector = SystemCollector::new_for_tests();
+        let mut sample = super::ProcSample {
+            stat: "cpu  0 0 0 100 0 0 0 0 0 0\n".into(),
+            meminfo: mem.into(),
+            ..Default::default()
+        };
+        let snap = collector.ingest(&sample, SampleTime::at(1_000)).unwrap();
+        assert!(!snap.pressure.supported);
+
+        sample.pressure_cpu = psi(0);
+        sample.pressure_memory = psi(0);
+        sample.pressure_io = psi(0);
+        collector.ingest(&sample, SampleTime::at(2_000)).unwrap();
+        sample.pressure_io = psi(250_000);
+        let snap = collector.ingest(&sample, SampleTime::at(3_000)).unwrap();
+
+        assert!(snap.pressure.supported);
+        assert!((snap.pressure.io.some_stall_percent - 25.0).abs() < 0.01);
+        assert!((snap.pressure.io.some_avg10 - 2.0).abs() < 1e-9);
+        assert_eq!(snap.pressure.cpu.some_stall_percent, 0.0);
+        assert_eq!(collector.bootstrap().unwrap().pressure.io.some_history.len(), 2);
+    }
+
+    #[test]
+    fn collector_attaches_frequency_history_to_cores() {
+        let mem = "MemTotal: 1000 kB\nMemAvailable: 400 kB\nSwapTotal: 0 kB\nSwapFree: 0 kB\n";
+        let freq = |khz: &str| crate::system::cpufreq::CpuFreqRaw {
+            cpu: 0,
+            scaling_cur_freq: khz.into(),
+            scaling_min_freq: "400000".into(),
+            scaling_max_freq: "4000000".into(),
+            scaling_governor: "schedutil".into(),
+            energy_performance_preference: None,
+        };
+        let mut collector = SystemCollector::new_for_tests();
+        let mut sample = super::ProcSample {
+            stat: "cpu  0 0 0 200 0 0 0 0 0 0\ncpu0 0 0 0 100 0 0 0 0 0 0\ncpu1 0 0 0 100 0 0 0 0 0 0\n".into(),
+            meminfo: mem.into(),
+            cpufreq: vec![freq("400000")],
+            ..Default::default()
+        };
+        collector.ingest(&sample, SampleTime::at(1_000)).unwrap();
+        sample.cpufreq = vec![freq("3900000")];
+        let snap = collector.ingest(&sample, SampleTime::at(2_000)).unwrap();
+
+        let cpu0 = snap.cores[0].frequency.as_ref().expect("cpu0 frequency");
+        assert_eq!(cpu0.cur_khz, 3_900_000);
+        assert_eq!(cpu0.governor, "schedutil");
+        assert_eq!(cpu0.history.len(), 1);
+        assert!(snap.cores[1].frequency.is_none());
+        let full = collector.bootstrap().unwrap();
+        assert_eq!(full.cores[0].frequency.as_ref().unwrap().history.len(), 2);
+    }
+
+    #[test]
+    fn collector_reports_battery_wear_and_time_to_empty() {
+        let mem = "MemTotal: 1000 kB\nMemAvailable: 400 kB\nSwapTotal: 0 kB\nSwapFree: 0 kB\n";
+        let mut collector = SystemCollector::new_for_tests();
+        let mut sample = super::ProcSample {
+            stat: "cpu  0 0 0 100 0 0 0 0 0 0\n".into(),
+            meminfo: mem.into(),
+            ..Default::default()
+        };
+        let snap = collector.ingest(&sample, SampleTime::at(1_000)).unwrap();
+        assert!(snap.power.batteries.is_empty());
+        assert_eq!(snap.power.ac_online, None);
+
+        sample.power_supplies = vec![
+            "POWER_SUPPLY_NAME=AC\nPOWER_SUPPLY_TYPE=Mains\nPOWER_SUPPLY_ONLINE=0\n".into(),
+            "POWER_SUPPLY_NAME=BAT0\nPOWER_SUPPLY_TYPE=Battery\nPOWER_SUPPLY_STATUS=Discharging\nPOWER_SUPPLY_CAPACITY=50\nPOWER_SUPPLY_POWER_NOW=10000000\nPOWER_SUPPLY_ENERGY_NOW=25000000\nPOWER_SUPPLY_ENERGY_FULL=45000000\nPOWER_SUPPLY_ENERGY_FULL_DESIGN=50000000\n".into(),
+        ];
+        let snap = collector.ingest(&sample, SampleTime::at(2_000)).unwrap();
+
+        assert_eq!(snap.power.ac_online, Some(false));
+        let battery = &snap.power.batteries[0];
+        assert!((battery.wear_percent.unwrap() - 10.0).abs() < 0.01);
+        assert_eq!(battery.time_to_empty_secs, Some(9_000));
+        assert_eq!(battery.discharge_history[0].value, 10.0);
+    }
+
+    #[test]
+    fn collector_categorizes_memory_usage() {
+        let mem = "MemTotal: 1000 kB\nMemFree: 100 kB\nMemAvailable: 500 kB\nBuffers: 50 kB\nCached: 300 kB\nShmem: 40 kB\nAnonPages: 350 kB\nSReclaimable: 60 kB\nSUnreclaim: 20 kB\nKernelStack: 10 kB\nPageTables: 5 kB\nCommitted_AS: 2000 kB\nCommitLimit: 1500 kB\nHugePages_Total: 2\nHugePages_Free: 1\nHugepagesize: 20 kB\nSwapTotal: 0 kB\nSwapFree: 0 kB\n";
+        let mut collector = SystemCollector::new_for_tests();
+        let sample = super::ProcSample {
+            stat: "cpu  0 0 0 100 0 0 0 0 0 0\n".into(),
+            meminfo: mem.into(),
+            ..Default::default()
+        };
+        let memory = collector.ingest(&sample, SampleTime::at(1_000)).unwrap().memory;
+
+        assert_eq!(memory.anon_bytes, 350 * 1024);
+        assert_eq!(memory.shmem_bytes, 40 * 1024);
+        assert_eq!(memory.hugepages_total, 2);
+        assert_eq!(memory.hugetlb_bytes, 40 * 1024);
+        assert_eq!(memory.committed_as_bytes, 2000 * 1024);
+        assert_eq!(memory.other_bytes, 65 * 1024);
+    }
+
+    #[test]
+    fn collector_reports_paging_rates_and_oom_kills() {
+        let mem = "MemTotal: 1000 kB\nMemAvailable: 400 kB\nSwapTotal: 0 kB\nSwapFree: 0 kB\n";
+        let mut collector = SystemCollector::new_for_tests();
+        let mut sample = super::ProcSample {
+            stat: "cpu  0 0 0 100 0 0 0 0 0 0\n".into(),
+            meminfo: mem.into(),
+            vmstat: "pgfault 1000\npgmajfault 10\npswpin 0\npswpout 0\noom_kill 2\n".into(),
+            ..Default::default()
+        };
+        collector.ingest(&sample, SampleTime::at(1_000)).unwrap();
+        sample.vmstat = "pgfault 1500\npgmajfault 30\npswpin 400\npswpout 800\noom_kill 3\n".into();
+        let vm = collector.ingest(&sample, SampleTime::at(2_000)).unwrap().vm;
+
+        assert!((vm.pgfault_ps - 500.0).abs() < 0.01);
+        assert!((vm.pgmajfault_ps - 20.0).abs() < 0.01);
+        assert!((vm.pswpout_ps - 800.0).abs() < 0.01);
+        assert_eq!(vm.oom_kill_total, 3);
+        assert_eq!(vm.oom_kills, 1);
+        assert_eq!(collector.bootstrap().unwrap().vm.oom_kill_history.len(), 2);
+    }
+
+    #[test]
+    fn collector_uses_running_time_and_marks_suspend_gaps() {
+        let mem = "MemTotal: 1000 kB\nMemAvailable: 400 kB\nSwapTotal: 0 kB\nSwapFree: 0 kB\n";
+        let net = |rx: u64| format!("  eth0: {rx} 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0\n");
+        let mut collector = SystemCollector::new_for_tests();
+        let mut sample = super::ProcSample {
+            stat: "cpu  0 0 0 100 0 0 0 0 0 0\n".into(),
+            meminfo: mem.into(),
+            net_dev: net(1_000),
+            ..Default::default()
+        };
+        collector.ingest(&sample, SampleTime::at(10_000)).unwrap();
+
+        // One second of running time, then an hour asleep.
+        sample.net_dev = net(3_000);
+        let resumed = SampleTime {
+            wall_ms: 3_611_000,
+            monotonic_ms: 11_000,
+            boot_ms: 3_611_000,
+        };
+        let snap = collector.ingest(&sample, resumed).unwrap();
+
+        assert!((snap.net_rx_bps - 2_000.0).abs() < 0.01);
+        assert_eq!(snap.gaps.len(), 1);
+        assert_eq!((snap.gaps[0].start_ms, snap.gaps[0].end_ms), (10_000, 3_611_000));
+    }
+
+    #[test]
+    fn collector_rebaselines_recreated_interfaces_and_reset_counters() {
+        let mem = "MemTotal: 1000 kB\nMemAvailable: 400 kB\nSwapTotal: 0 kB\nSwapFree: 0 kB\n";
+        let net = |rx: u64| format!("   wg0: {rx} 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0\n");
+        let mut collector = SystemCollector::new_for_tests();
+        let mut sample = super::ProcSample {
+            stat: "cpu  0 0 0 100 0 0 0 0 0 0\n".into(),
+            meminfo: mem.into(),
+            net_dev: net(1_000),
+            interface_indexes: [("wg0".to_string(), 5)].into(),
+            vmstat: "pgfault 90000\n".into(),
+            ..Default::default()
+        };
+        collector.ingest(&sample, SampleTime::at(1_000)).unwrap();
+
+        // wg0 is torn down and re-created with a new ifindex and higher counters.
+        sample.net_dev = net(50_000);
+        sample.interface_indexes = [("wg0".to_string(), 9)].into();
+        sample.vmstat = "pgfault 400\n".into();
+        let snap = collector.ingest(&sample, SampleTime::at(2_000)).unwrap();
+        assert_eq!(snap.net_interfaces[0].rx_bps, 0.0);
+        assert_eq!(snap.net_interfaces[0].rx_history.len(), 1);
+        assert!((snap.vm.pgfault_ps - 400.0).abs() < 0.01);
+
+        sample.net_dev = net(51_000);
+        let snap = collector.ingest(&sample, SampleTime::at(3_000)).unwrap();
+        assert!((snap.net_interfaces[0].rx_bps - 1_000.0).abs() < 0.01);
     }
 }
@@ -0,0 +1,96 @@
+use std::{fs, path::Path};
+
+#[derive(Debug, Clone, Default)]
+pub struct CpuFreqRaw {
+    pub cpu: usize,
+    pub scaling_cur_freq: String,
+    pub scaling_min_freq: String,
+    pub scaling_max_freq: String,
+    pub scaling_governor: String,
+    pub energy_performance_preference: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CpuFreq {
+    pub cpu: usize,
+    pub cur_khz: u64,
+    pub min_khz: u64,
+    pub max_khz: u64,
+    pub governor: String,
+    pub energy_performance_preference: Option<String>,
+}
+
+pub fn parse_cpufreq(raw: &CpuFreqRaw) -> Option<CpuFreq> {
+    let khz = |value: &str| value.trim().parse::<u64>().ok();
+    Some(CpuFreq {
+        cpu: raw.cpu,
+        cur_khz: khz(&raw.scaling_cur_freq)?,
+        min_khz: khz(&raw.scaling_min_freq).unwrap_or(0),
+        max_khz: khz(&raw.scaling_max_freq).unwrap_or(0),
+        governor: raw.scaling_governor.trim().to_string(),
+        energy_performance_preference: raw
+            .energy_performance_preference
+            .as_deref()
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(str::to_string),
+    })
+}
+
+pub fn read_cpufreq_raw(sys_root: &Path) -> Vec<CpuFreqRaw> {
+    let cpu_dir = sys_root.join("devices/system/cpu");
+    let Ok(entries) = fs::read_dir(&cpu_dir) else {
+        return Vec::new();
+    };
+
+    let mut raws: Vec<CpuFreqRaw> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let cpu = name.strip_prefix("cpu")?.parse::<usize>().ok()?;
+            let dir = entry.path().join("cpufreq");
+            let read = |file: &str| fs::read_to_string(dir.join(file)).ok();
+            Some(CpuFreqRaw {
+                cpu,
+                scaling_cur_freq: read("scaling_cur_freq")?,
+                scaling_min_freq: read("scaling_min_freq").unwrap_or_default(),
+                scaling_max_freq: read("scaling_max_freq").unwrap_or_default(),
+                scaling_governor: read("scaling_governor").unwrap_or_default(),
+                energy_performance_preference: read("energy_performance_preference"),
+            })
+        })
+        .collect();
+    raws.sort_by_key(|raw| raw.cpu);
+    raws
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CpuFreqRaw;
+
+    #[test]
+    fn parse_cpufreq_reads_khz_governor_and_epp() {
+        let raw = CpuFreqRaw {
+            cpu: 3,
+            scaling_cur_freq: "800000\n".into(),
+            scaling_min_freq: "400000\n".into(),
+            scaling_max_freq: "4700000\n".into(),
+            scaling_governor: "powersave\n".into(),
+            energy_performance_preference: Some("balance_power\n".into()),
+        };
+        let freq = super::parse_cpufreq(&raw).expect("cpufreq");
+        assert_eq!(freq.cpu, 3);
+        assert_eq!((freq.cur_khz, freq.min_khz, freq.max_khz), (800_000, 400_000, 4_700_000));
+        assert_eq!(freq.governor, "powersave");
+        assert_eq!(freq.energy_performance_preference.as_deref(), Some("balance_power"));
+    }
+
+    #[test]
+    fn parse_cpufreq_requires_current_frequency() {
+        let raw = CpuFreqRaw {
+            scaling_cur_freq: "<unknown>\n".into(),
+            ..CpuFreqRaw::default()
+        };
+        assert!(super::parse_cpufreq(&raw).is_none());
+    }
+}
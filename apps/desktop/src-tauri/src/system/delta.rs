@@ -1,6 +1,12 @@
+use crate::system::{models::CpuModes, procfs::CpuTotals};
+
+// Counters that are `unsigned int` in the kernel (per-CPU interrupt counts)
+// wrap at 2^32.
+const WRAP_32: u64 = 1 << 32;
+
 pub fn cpu_percent(prev: (u64, u64), next: (u64, u64)) -> f32 {
-    let idle_delta = next.0.saturating_sub(prev.0) as f32;
-    let total_delta = next.1.saturating_sub(prev.1) as f32;
+    let idle_delta = counter_delta(prev.0, next.0) as f32;
+    let total_delta = counter_delta(prev.1, next.1) as f32;
 
     if total_delta <= 0.0 {
         return 0.0;
@@ -9,6 +15,52 @@ pub fn cpu_percent(prev: (u64, u64), next: (u64, u64)) -> f32 {
     ((total_delta - idle_delta) / total_delta) * 100.0
 }
 
+pub fn counter_delta(prev: u64, next: u64) -> u64 {
+    // Kernel counters only move forward. A 32-bit counter that was in the top
+    // half of its range and comes back small has wrapped; any other drop means
+    // the source was reset (driver reload, device re-attached, CPU re-onlined),
+    // so count from zero.
+    if next >= prev {
+        next - prev
+    } else if prev < WRAP_32 && WRAP_32 - prev + next < WRAP_32 / 2 {
+        WRAP_32 - prev + next
+    } else {
+        next
+    }
+}
+
+pub fn rate_per_sec(prev: u64, next: u64, elapsed_ms: f64) -> f64 {
+    if elapsed_ms <= 0.0 {
+        return 0.0;
+    }
+    (counter_delta(prev, next) as f64) * 1000.0 / elapsed_ms
+}
+
+pub fn cpu_mode_percent(prev: &CpuTotals, next: &CpuTotals) -> CpuModes<f32> {
+    let total_delta = counter_delta(prev.total, next.total) as f32;
+    if total_delta <= 0.0 {
+        return CpuModes::default();
+    }
+
+    let pct = |delta: u64| (delta as f32 / total_delta) * 100.0;
+    let guest = counter_delta(prev.guest, next.guest);
+    let guest_nice = counter_delta(prev.guest_nice, next.guest_nice);
+
+    // user/nice include guest time, so split it out to keep the modes summing to 100%.
+    CpuModes {
+        user: pct(counter_delta(prev.user, next.user).saturating_sub(guest)),
+        nice: pct(counter_delta(prev.nice, next.nice).saturating_sub(guest_nice)),
+        system: pct(counter_delta(prev.system, next.system)),
+        idle: pct(counter_delta(prev.idle, next.idle)),
+        iowait: pct(counter_delta(prev.iowait, next.iowait)),
+        irq: pct(counter_delta(prev.irq, next.irq)),
+        softirq: pct(counter_delta(prev.softirq, next.softirq)),
+        steal: pct(counter_delta(prev.steal, next.steal)),
+        guest: pct(guest),
+        guest_nice: pct(guest_nice),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     #[test]
@@ -18,4 +70,31 @@ mod tests {
         let value = super::cpu_percent(prev, next);
         assert!((value - 50.0).abs() < 0.01);
     }
+
+    #[test]
+    fn rate_per_sec_counts_from_zero_after_reset() {
+        assert!((super::rate_per_sec(1_000, 3_000, 2_000.0) - 1_000.0).abs() < 0.01);
+        assert!((super::rate_per_sec(90_000, 500, 1_000.0) - 500.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn counter_delta_spans_a_32_bit_wrap() {
+        assert_eq!(super::counter_delta(u32::MAX as u64 - 9, 20), 30);
+        // A drop from the bottom half of the range, or from a 64-bit value, is a reset.
+        assert_eq!(super::counter_delta(1_000_000, 20), 20);
+        assert_eq!(super::counter_delta(1 << 40, 20), 20);
+    }
+
+    #[test]
+    fn cpu_mode_percent_splits_guest_out_of_user() {
+        let prev = crate::system::procfs::parse_cpu_totals("cpu  100 0 100 700 0 0 0 0 0 0\n").unwrap();
+        let next = crate::system::procfs::parse_cpu_totals("cpu  160 0 110 710 10 0 0 10 40 0\n").unwrap();
+        let modes = super::cpu_mode_percent(&prev, &next);
+        assert!((modes.user - 20.0).abs() < 0.01);
+        assert!((modes.guest - 40.0).abs() < 0.01);
+        assert!((modes.system - 10.0).abs() < 0.01);
+        assert!((modes.idle - 10.0).abs() < 0.01);
+        assert!((modes.iowait - 10.0).abs() < 0.01);
+        assert!((modes.steal - 10.0).abs() < 0.01);
+    }
 }
@@ -0,0 +1,201 @@
+use std::{
+    env, fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::system::models::{ExportFormat, RollupPoint};
+
+const INFLUX_MEASUREMENT: &str = "lsm";
+
+// xdg-user-dirs keeps `XDG_DOWNLOAD_DIR="$HOME/Downloads"` in user-dirs.dirs;
+// the variable is rarely exported into the environment.
+fn user_dirs_download(raw: &str, home: &Path) -> Option<PathBuf> {
+    raw.lines().find_map(|line| {
+        let value = line.trim().strip_prefix("XDG_DOWNLOAD_DIR=")?.trim().trim_matches('"');
+        let path = match value.strip_prefix("$HOME") {
+            Some(rest) => home.join(rest.trim_start_matches('/')),
+            None => PathBuf::from(value),
+        };
+        path.is_absolute().then_some(path)
+    })
+}
+
+// Exports may only be written below this directory.
+pub fn default_export_dir() -> Option<PathBuf> {
+    if let Some(dir) = env::var_os("XDG_DOWNLOAD_DIR").map(PathBuf::from).filter(|path| path.is_absolute()) {
+        return Some(dir);
+    }
+    let home = PathBuf::from(env::var_os("HOME")?);
+    let config_home = env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+        .unwrap_or_else(|| home.join(".config"));
+    fs::read_to_string(config_home.join("user-dirs.dirs"))
+        .ok()
+        .and_then(|raw| user_dirs_download(&raw, &home))
+        .or_else(|| Some(home.join("Downloads")))
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn json_string(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if c < ' ' => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn json_number(value: f64) -> String {
+    if value.is_finite() {
+        value.to_string()
+    } else {
+        "null".to_string()
+    }
+}
+
+fn influx_tag(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, ',' | '=' | ' ') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+// Writes points one at a time, so an export never holds more than the series
+// currently being copied out.
+pub struct Exporter<W: Write> {
+    out: W,
+    format: ExportFormat,
+    rows: u64,
+}
+
+impl<W: Write> Exporter<W> {
+    pub fn new(mut out: W, format: ExportFormat) -> io::Result<Self> {
+        if format == ExportFormat::Csv {
+            writeln!(out, "series,ts_ms,min,avg,max")?;
+        }
+        Ok(Self { out, format, rows: 0 })
+    }
+
+    pub fn write(&mut self, series: &str, point: &RollupPoint) -> io::Result<()> {
+        let RollupPoint { ts_ms, min, avg, max } = *point;
+        match self.format {
+            ExportFormat::Csv => writeln!(self.out, "{},{ts_ms},{min},{avg},{max}", csv_field(series))?,
+            ExportFormat::Jsonl => writeln!(
+                self.out,
+                "{{\"series\":{},\"ts_ms\":{ts_ms},\"min\":{},\"avg\":{},\"max\":{}}}",
+                json_string(series),
+                json_number(min),
+                json_number(avg),
+                json_number(max)
+            )?,
+            ExportFormat::Influx => {
+                // Line protocol has no way to spell NaN or infinity.
+                if ![min, avg, max].iter().all(|value| value.is_finite()) {
+                    return Ok(());
+                }
+                writeln!(
+                    self.out,
+                    "{INFLUX_MEASUREMENT},series={} min={min},avg={avg},max={max} {}",
+                    influx_tag(series),
+                    ts_ms.saturating_mul(1_000_000)
+                )?
+            }
+        }
+        self.rows += 1;
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> io::Result<u64> {
+        self.out.flush()?;
+        Ok(self.rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use super::Exporter;
+    use crate::system::models::{ExportFormat, RollupPoint};
+
+    #[test]
+    fn user_dirs_download_expands_home_and_skips_relative_paths() {
+        let home = Path::new("/home/ada");
+        let raw = "# written by xdg-user-dirs-update\nXDG_DESKTOP_DIR=\"$HOME/Desktop\"\nXDG_DOWNLOAD_DIR=\"$HOME/Téléchargements\"\n";
+        assert_eq!(
+            super::user_dirs_download(raw, home),
+            Some(PathBuf::from("/home/ada/Téléchargements"))
+        );
+        assert_eq!(
+            super::user_dirs_download("XDG_DOWNLOAD_DIR=\"/srv/exports\"\n", home),
+            Some(PathBuf::from("/srv/exports"))
+        );
+        assert_eq!(super::user_dirs_download("XDG_DOWNLOAD_DIR=\"Downloads\"\n", home), None);
+        assert_eq!(super::user_dirs_download("", home), None);
+    }
+
+    fn export(format: ExportFormat, series: &str, points: &[RollupPoint]) -> String {
+        let mut exporter = Exporter::new(Vec::new(), format).unwrap();
+        for point in points {
+            exporter.write(series, point).unwrap();
+        }
+        let rows = exporter.rows;
+        let out = String::from_utf8(exporter.out).unwrap();
+        assert_eq!(out.lines().count() as u64, rows + u64::from(format == ExportFormat::Csv));
+        out
+    }
+
+    const POINT: RollupPoint = RollupPoint {
+        ts_ms: 1_000,
+        min: 1.0,
+        avg: 2.5,
+        max: 4.0,
+    };
+
+    #[test]
+    fn exporter_writes_each_format() {
+        assert_eq!(
+            export(ExportFormat::Csv, "net/eth0/rx", &[POINT]),
+            "series,ts_ms,min,avg,max\nnet/eth0/rx,1000,1,2.5,4\n"
+        );
+        assert_eq!(
+            export(ExportFormat::Jsonl, "cpu", &[POINT]),
+            "{\"series\":\"cpu\",\"ts_ms\":1000,\"min\":1,\"avg\":2.5,\"max\":4}\n"
+        );
+        assert_eq!(
+            export(ExportFormat::Influx, "cpu", &[POINT]),
+            "lsm,series=cpu min=1,avg=2.5,max=4 1000000000\n"
+        );
+    }
+
+    #[test]
+    fn exporter_escapes_series_names() {
+        let odd = "sensor/my chip,a=\"b\"";
+        assert!(export(ExportFormat::Csv, odd, &[POINT]).contains("\"sensor/my chip,a=\"\"b\"\"\",1000"));
+        assert!(export(ExportFormat::Jsonl, odd, &[POINT]).contains(r#""series":"sensor/my chip,a=\"b\"""#));
+        assert!(export(ExportFormat::Influx, odd, &[POINT]).starts_with(r#"lsm,series=sensor/my\ chip\,a\="b" "#));
+
+        let nan = RollupPoint { avg: f64::NAN, ..POINT };
+        assert!(export(ExportFormat::Jsonl, "cpu", &[nan]).contains("\"avg\":null"));
+        assert_eq!(export(ExportFormat::Influx, "cpu", &[nan]), "");
+    }
+}
@@ -0,0 +1,242 @@
+use std::{collections::BTreeMap, ffi::CString, mem::MaybeUninit};
+
+use crate::system::{
+    models::{FilesystemUsage, MetricPoint},
+    ring_buffer::RingBuffer,
+};
+
+const PSEUDO_FS_TYPES: &[&str] = &[
+    "autofs",
+    "binfmt_misc",
+    "bpf",
+    "cgroup",
+    "cgroup2",
+    "configfs",
+    "debugfs",
+    "devpts",
+    "devtmpfs",
+    "efivarfs",
+    "fusectl",
+    "hugetlbfs",
+    "mqueue",
+    "nsfs",
+    "overlay",
+    "proc",
+    "pstore",
+    "ramfs",
+    "rpc_pipefs",
+    "securityfs",
+    "squashfs",
+    "sysfs",
+    "tmpfs",
+    "tracefs",
+];
+
+#[derive(Debug, Clone)]
+pub struct MountEntry {
+    pub device: String,
+    pub mount_point: String,
+    pub fs_type: String,
+    pub source: String,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct FsCapacity {
+    pub total_bytes: u64,
+    pub free_bytes: u64,
+    pub available_bytes: u64,
+    pub total_inodes: u64,
+    pub free_inodes: u64,
+}
+
+pub fn is_pseudo_fs(fs_type: &str) -> bool {
+    PSEUDO_FS_TYPES.contains(&fs_type) || fs_type.starts_with("fuse.portal") || fs_type == "fuse.gvfsd-fuse"
+}
+
+fn unescape_mount_path(raw: &str) -> String {
+    // mountinfo escapes space, tab, newline and backslash as three-digit octal.
+    let bytes = raw.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let digits = bytes.get(i + 1..i + 4).unwrap_or_default();
+        if bytes[i] == b'\\' && digits.len() == 3 && digits.iter().all(|b| (b'0'..=b'7').contains(b)) {
+            let code = digits.iter().fold(0_u32, |acc, b| acc * 8 + (b - b'0') as u32);
+            out.push(code as u8);
+            i += 4;
+            continue;
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).to_string()
+}
+
+pub fn parse_mountinfo(input: &str) -> Vec<MountEntry> {
+    let mut mounts: Vec<MountEntry> = Vec::new();
+
+    for line in input.lines() {
+        let Some((left, right)) = line.split_once(" - ") else {
+            continue;
+        };
+        let left: Vec<&str> = left.split_whitespace().collect();
+        let mut right = right.split_whitespace();
+        if left.len() < 5 {
+            continue;
+        }
+        let (Some(fs_type), Some(source)) = (right.next(), right.next()) else {
+            continue;
+        };
+        if is_pseudo_fs(fs_type) {
+            continue;
+        }
+
+        // Bind mounts share the device of the original mount; report it once.
+        let device = left[2].to_string();
+        if mounts.iter().any(|mount| mount.device == device) {
+            continue;
+        }
+
+        mounts.push(MountEntry {
+            device,
+            mount_point: unescape_mount_path(left[4]),
+            fs_type: fs_type.to_string(),
+            source: unescape_mount_path(source),
+        });
+    }
+
+    mounts
+}
+
+// The statvfs field widths differ between 32- and 64-bit targets.
+#[allow(clippy::unnecessary_cast)]
+pub fn statvfs(path: &str) -> Option<FsCapacity> {
+    let c_path = CString::new(path).ok()?;
+    let mut stat = MaybeUninit::<libc::statvfs>::uninit();
+    let rc = unsafe { libc::statvfs(c_path.as_ptr(), stat.as_mut_ptr()) };
+    if rc != 0 {
+        return None;
+    }
+    let stat = unsafe { stat.assume_init() };
+    let frsize = stat.f_frsize as u64;
+    Some(FsCapacity {
+        total_bytes: (stat.f_blocks as u64).saturating_mul(frsize),
+        free_bytes: (stat.f_bfree as u64).saturating_mul(frsize),
+        available_bytes: (stat.f_bavail as u64).saturating_mul(frsize),
+        total_inodes: stat.f_files as u64,
+        free_inodes: stat.f_ffree as u64,
+    })
+}
+
+fn percent(used: u64, total: u64) -> f64 {
+    if total == 0 {
+        return 0.0;
+    }
+    used as f64 / total as f64 * 100.0
+}
+
+pub struct FilesystemMonitor {
+    history_cap: usize,
+    history: BTreeMap<String, RingBuffer<MetricPoint>>,
+}
+
+impl FilesystemMonitor {
+    pub fn new(history_cap: usize) -> Self {
+        Self {
+            history_cap,
+            history: BTreeMap::new(),
+        }
+    }
+
+    pub fn ingest(
+        &mut self,
+        mounts: &[(MountEntry, FsCapacity)],
+        now_ms: u64,
+        include_history: bool,
+    ) -> Vec<FilesystemUsage> {
+        self.history
+            .retain(|mount_point, _| mounts.iter().any(|(mount, _)| &mount.mount_point == mount_point));
+
+        mounts
+            .iter()
+            .map(|(mount, capacity)| {
+                let used_bytes = capacity.total_bytes.saturating_sub(capacity.free_bytes);
+                // Same as df: reserved blocks are neither used nor available to users.
+                let used_percent = percent(used_bytes, used_bytes + capacity.available_bytes);
+                let inodes_used = capacity.total_inodes.saturating_sub(capacity.free_inodes);
+
+                let cap = self.history_cap;
+                let history = self
+                    .history
+                    .entry(mount.mount_point.clone())
+                    .or_insert_with(|| RingBuffer::new(cap));
+                history.push(MetricPoint {
+                    ts_ms: now_ms as i64,
+                    value: used_percent,
+                });
+
+                FilesystemUsage {
+                    mount_point: mount.mount_point.clone(),
+                    source: mount.source.clone(),
+                    fs_type: mount.fs_type.clone(),
+                    total_bytes: capacity.total_bytes,
+                    used_bytes,
+                    free_bytes: capacity.free_bytes,
+                    available_bytes: capacity.available_bytes,
+                    used_percent,
+                    inodes_total: capacity.total_inodes,
+                    inodes_used,
+                    inodes_free: capacity.free_inodes,
+                    inodes_used_percent: percent(inodes_used, capacity.total_inodes),
+                    history: include_history.then(|| history.snapshot()),
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FilesystemMonitor, FsCapacity};
+
+    const MOUNTINFO: &str = "\
+22 1 8:2 / / rw,relatime shared:1 - ext4 /dev/sda2 rw
+23 22 0:21 / /proc rw,nosuid - proc proc rw
+24 22 0:5 / /dev rw,nosuid shared:2 - devtmpfs udev rw
+25 22 8:3 / /var rw,relatime shared:3 - xfs /dev/sda3 rw
+26 22 0:26 / /run rw,nosuid - tmpfs tmpfs rw
+27 22 8:3 /lib/docker /srv/docker rw,relatime - xfs /dev/sda3 rw
+28 22 8:17 / /mnt/my\\040disk rw - ext4 /dev/sdb1 rw
+";
+
+    #[test]
+    fn parse_mountinfo_skips_pseudo_and_bind_mounts() {
+        let mounts = super::parse_mountinfo(MOUNTINFO);
+        let points: Vec<&str> = mounts.iter().map(|m| m.mount_point.as_str()).collect();
+        assert_eq!(points, vec!["/", "/var", "/mnt/my disk"]);
+        assert_eq!(mounts[1].fs_type, "xfs");
+        assert_eq!(mounts[1].source, "/dev/sda3");
+    }
+
+    #[test]
+    fn monitor_reports_capacity_and_inodes() {
+        let mounts = super::parse_mountinfo(MOUNTINFO);
+        let capacity = FsCapacity {
+            total_bytes: 1_000,
+            free_bytes: 150,
+            available_bytes: 100,
+            total_inodes: 200,
+            free_inodes: 50,
+        };
+        let input = vec![(mounts[1].clone(), capacity)];
+        let mut monitor = FilesystemMonitor::new(10);
+        monitor.ingest(&input, 1_000, false);
+        let usage = monitor.ingest(&input, 2_000, true);
+
+        assert_eq!(usage[0].used_bytes, 850);
+        assert!((usage[0].used_percent - 850.0 / 950.0 * 100.0).abs() < 0.01);
+        assert_eq!(usage[0].inodes_used, 150);
+        assert!((usage[0].inodes_used_percent - 75.0).abs() < 0.01);
+        assert_eq!(usage[0].history.as_ref().map(Vec::len), Some(2));
+    }
+}
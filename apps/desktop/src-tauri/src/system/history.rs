@@ -0,0 +1,388 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::system::{
+    models::{HistoryRange, HistorySeries, MetricPoint, Resolution, RollupPoint},
+    ring_buffer::RingBuffer,
+};
+
+// The sampler never runs faster than this, so the point caps are sized for
+// it. They only bound memory; retention itself is enforced by timestamp.
+pub const MIN_SAMPLE_MS: u64 = 500;
+pub const TEN_SECONDS_MS: i64 = 10_000;
+pub const ONE_MINUTE_MS: i64 = 60_000;
+// Retention comes from the webview, so every field is clamped to these.
+pub const MAX_RAW_MINUTES: u64 = 24 * 60;
+pub const MAX_TEN_SECOND_HOURS: u64 = 7 * 24;
+pub const MAX_ONE_MINUTE_DAYS: u64 = 90;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RetentionConfig {
+    pub raw_minutes: u64,
+    pub ten_second_hours: u64,
+    pub one_minute_days: u64,
+}
+
+impl Default for RetentionConfig {
+    fn default() -> Self {
+        Self {
+            raw_minutes: 10,
+            ten_second_hours: 6,
+            one_minute_days: 7,
+        }
+    }
+}
+
+fn window_ms(count: u64, unit_ms: u64) -> u64 {
+    count.max(1).saturating_mul(unit_ms)
+}
+
+impl RetentionConfig {
+    pub fn clamped(self) -> Self {
+        Self {
+            raw_minutes: self.raw_minutes.clamp(1, MAX_RAW_MINUTES),
+            ten_second_hours: self.ten_second_hours.clamp(1, MAX_TEN_SECOND_HOURS),
+            one_minute_days: self.one_minute_days.clamp(1, MAX_ONE_MINUTE_DAYS),
+        }
+    }
+
+    fn raw_window_ms(&self) -> i64 {
+        i64::try_from(window_ms(self.raw_minutes, 60_000)).unwrap_or(i64::MAX)
+    }
+
+    fn ten_second_window_ms(&self) -> i64 {
+        i64::try_from(window_ms(self.ten_second_hours, 3_600_000)).unwrap_or(i64::MAX)
+    }
+
+    fn one_minute_window_ms(&self) -> i64 {
+        i64::try_from(window_ms(self.one_minute_days, 86_400_000)).unwrap_or(i64::MAX)
+    }
+
+    fn raw_cap(&self) -> usize {
+        usize::try_from(window_ms(self.raw_minutes, 60_000) / MIN_SAMPLE_MS).unwrap_or(usize::MAX)
+    }
+
+    fn ten_second_cap(&self) -> usize {
+        usize::try_from(window_ms(self.ten_second_hours, 3_600_000) / TEN_SECONDS_MS as u64).unwrap_or(usize::MAX)
+    }
+
+    fn one_minute_cap(&self) -> usize {
+        usize::try_from(window_ms(self.one_minute_days, 86_400_000) / ONE_MINUTE_MS as u64).unwrap_or(usize::MAX)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    start_ms: i64,
+    min: f64,
+    max: f64,
+    sum: f64,
+    count: u32,
+}
+
+impl Bucket {
+    fn new(start_ms: i64, value: f64) -> Self {
+        Self {
+            start_ms,
+            min: value,
+            max: value,
+            sum: value,
+            count: 1,
+        }
+    }
+
+    fn add(&mut self, value: f64) {
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        self.sum += value;
+        self.count += 1;
+    }
+
+    fn rollup(&self) -> RollupPoint {
+        RollupPoint {
+            ts_ms: self.start_ms,
+            min: self.min,
+            avg: self.sum / self.count as f64,
+            max: self.max,
+        }
+    }
+}
+
+struct RollupTier {
+    width_ms: i64,
+    points: RingBuffer<RollupPoint>,
+    pending: Option<Bucket>,
+}
+
+impl RollupTier {
+    fn new(width_ms: i64, cap: usize) -> Self {
+        Self {
+            width_ms,
+            points: RingBuffer::new(cap),
+            pending: None,
+        }
+    }
+
+    fn push(&mut self, point: &MetricPoint) -> Option<RollupPoint> {
+        let start_ms = point.ts_ms - point.ts_ms.rem_euclid(self.width_ms);
+        match self.pending.as_mut() {
+            Some(bucket) if bucket.start_ms == start_ms => {
+                bucket.add(point.value);
+                None
+            }
+            _ => {
+                let done = self.pending.replace(Bucket::new(start_ms, point.value))?.rollup();
+                self.points.push(done);
+                Some(done)
+            }
+        }
+    }
+
+    // Buckets that ended at or before the cutoff are gone entirely.
+    fn trim(&mut self, cutoff_ms: i64) {
+        let width_ms = self.width_ms;
+        self.points.drop_front_while(|point| point.ts_ms + width_ms <= cutoff_ms);
+    }
+
+    fn oldest_ms(&self) -> Option<i64> {
+        self.points
+            .first()
+            .map(|point| point.ts_ms)
+            .or(self.pending.map(|bucket| bucket.start_ms))
+    }
+
+    // The bucket still filling up is included so live charts reach "now".
+    fn range(&self, start_ms: i64, end_ms: i64) -> Vec<RollupPoint> {
+        self.points
+            .iter()
+            .copied()
+            .chain(self.pending.map(|bucket| bucket.rollup()))
+            .filter(|point| point.ts_ms + self.width_ms > start_ms && point.ts_ms <= end_ms)
+            .collect()
+    }
+}
+
+struct TieredSeries {
+    raw: RingBuffer<MetricPoint>,
+    ten_seconds: RollupTier,
+    one_minute: RollupTier,
+}
+
+impl TieredSeries {
+    fn new(retention: &RetentionConfig) -> Self {
+        Self {
+            raw: RingBuffer::new(retention.raw_cap()),
+            ten_seconds: RollupTier::new(TEN_SECONDS_MS, retention.ten_second_cap()),
+            one_minute: RollupTier::new(ONE_MINUTE_MS, retention.one_minute_cap()),
+        }
+    }
+
+    fn push(&mut self, point: MetricPoint, retention: &RetentionConfig) -> Option<RollupPoint> {
+        let completed = self.ten_seconds.push(&point);
+        self.one_minute.push(&point);
+        let newest_ms = point.ts_ms;
+        self.raw.push(point);
+        self.trim(newest_ms, retention);
+        completed
+    }
+
+    // Drops whatever has aged out of each tier's window, measured back from
+    // the newest sample so the sampling interval does not matter.
+    fn trim(&mut self, newest_ms: i64, retention: &RetentionConfig) {
+        let raw_cutoff = newest_ms.saturating_sub(retention.raw_window_ms());
+        self.raw.drop_front_while(|point| point.ts_ms <= raw_cutoff);
+        self.ten_seconds.trim(newest_ms.saturating_sub(retention.ten_second_window_ms()));
+        self.one_minute.trim(newest_ms.saturating_sub(retention.one_minute_window_ms()));
+    }
+
+    fn pick(&self, start_ms: i64, resolution: Resolution) -> Resolution {
+        if resolution != Resolution::Auto {
+            return resolution;
+        }
+        // The finest tier that still reaches back to the start of the range.
+        let reaches = |oldest: Option<i64>| oldest.is_some_and(|oldest| oldest <= start_ms);
+        if reaches(self.raw.first().map(|point| point.ts_ms)) {
+            Resolution::Raw
+        } else if reaches(self.ten_seconds.oldest_ms()) {
+            Resolution::TenSeconds
+        } else {
+            Resolution::OneMinute
+        }
+    }
+
+    fn range(&self, start_ms: i64, end_ms: i64, resolution: Resolution) -> Vec<RollupPoint> {
+        match resolution {
+            Resolution::Raw | Resolution::Auto => self
+                .raw
+                .iter()
+                .filter(|point| point.ts_ms >= start_ms && point.ts_ms <= end_ms)
+                .map(RollupPoint::from)
+                .collect(),
+            Resolution::TenSeconds => self.ten_seconds.range(start_ms, end_ms),
+            Resolution::OneMinute => self.one_minute.range(start_ms, end_ms),
+        }
+    }
+
+    fn resize(&mut self, retention: &RetentionConfig) {
+        self.raw.resize(retention.raw_cap());
+        self.ten_seconds.points.resize(retention.ten_second_cap());
+        self.one_minute.points.resize(retention.one_minute_cap());
+        if let Some(newest_ms) = self.raw.iter().last().map(|point| point.ts_ms) {
+            self.trim(newest_ms, retention);
+        }
+    }
+}
+
+pub struct TieredHistory {
+    retention: RetentionConfig,
+    series: BTreeMap<HistorySeries, TieredSeries>,
+}
+
+impl TieredHistory {
+    pub fn new(retention: RetentionConfig) -> Self {
+        let retention = retention.clamped();
+        Self {
+            retention,
+            series: HistorySeries::ALL
+                .iter()
+                .map(|series| (*series, TieredSeries::new(&retention)))
+                .collect(),
+        }
+    }
+
+    // Raw points newer than `since_ms`, walking back from the newest.
+    pub fn raw_after(&self, series: HistorySeries, since_ms: i64) -> Vec<MetricPoint> {
+        self.series
+            .get(&series)
+            .map_or_else(Vec::new, |tiers| tiers.raw.tail_while(|point| point.ts_ms > since_ms))
+    }
+
+    pub fn retention(&self) -> RetentionConfig {
+        self.retention
+    }
+
+    pub fn set_retention(&mut self, retention: RetentionConfig) {
+        let retention = retention.clamped();
+        self.retention = retention;
+        for series in self.series.values_mut() {
+            series.resize(&retention);
+        }
+    }
+
+    // Returns the 10s rollup that this point closed, if any.
+    pub fn push(&mut self, series: HistorySeries, point: MetricPoint) -> Option<RollupPoint> {
+        self.series.get_mut(&series)?.push(point, &self.retention)
+    }
+
+    pub fn query(
+        &self,
+        series: HistorySeries,
+        start_ms: Option<i64>,
+        end_ms: Option<i64>,
+        resolution: Resolution,
+    ) -> HistoryRange {
+        let start_ms = start_ms.unwrap_or(i64::MIN);
+        let end_ms = end_ms.unwrap_or(i64::MAX);
+        let Some(tiers) = self.series.get(&series) else {
+            return HistoryRange {
+                series,
+                resolution,
+                points: Vec::new(),
+            };
+        };
+        let resolution = tiers.pick(start_ms, resolution);
+        HistoryRange {
+            series,
+            resolution,
+            points: tiers.range(start_ms, end_ms, resolution),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RetentionConfig, TieredHistory};
+    use crate::system::models::{HistorySeries, MetricPoint, Resolution};
+
+    fn filled(retention: RetentionConfig) -> TieredHistory {
+        let mut history = TieredHistory::new(retention);
+        // Half an hour of 1s samples ramping 0..1799.
+        for second in 0..1_800 {
+            history.push(
+                HistorySeries::Cpu,
+                MetricPoint {
+                    ts_ms: second * 1_000,
+                    value: second as f64,
+                },
+            );
+        }
+        history
+    }
+
+    #[test]
+    fn rollups_keep_min_avg_and_max() {
+        let history = filled(RetentionConfig::default());
+        let range = history.query(HistorySeries::Cpu, Some(0), Some(59_999), Resolution::TenSeconds);
+        assert_eq!(range.points.len(), 6);
+        assert_eq!(range.points[1].ts_ms, 10_000);
+        assert_eq!((range.points[1].min, range.points[1].max), (10.0, 19.0));
+        assert!((range.points[1].avg - 14.5).abs() < 1e-9);
+
+        let minutes = history.query(HistorySeries::Cpu, None, None, Resolution::OneMinute);
+        assert_eq!(minutes.points.len(), 30);
+        assert_eq!(minutes.points[29].max, 1_799.0);
+    }
+
+    #[test]
+    fn auto_picks_the_finest_tier_covering_the_range() {
+        let retention = RetentionConfig {
+            raw_minutes: 5,
+            ..RetentionConfig::default()
+        };
+        let history = filled(retention);
+
+        let recent = history.query(HistorySeries::Cpu, Some(1_700_000), None, Resolution::Auto);
+        assert_eq!(recent.resolution, Resolution::Raw);
+        assert_eq!(recent.points.len(), 100);
+
+        let older = history.query(HistorySeries::Cpu, Some(60_000), Some(120_000), Resolution::Auto);
+        assert_eq!(older.resolution, Resolution::TenSeconds);
+        assert_eq!(older.points.len(), 7);
+    }
+
+    #[test]
+    fn shrinking_retention_drops_oldest_points() {
+        let mut history = filled(RetentionConfig::default());
+        // The default ten minutes hold 600 points at 1s, not the 1200 the
+        // 500ms-sized cap would fit.
+        let raw = history.query(HistorySeries::Cpu, None, None, Resolution::Raw);
+        assert_eq!(raw.points.len(), 600);
+
+        history.set_retention(RetentionConfig {
+            raw_minutes: 1,
+            ..RetentionConfig::default()
+        });
+        // One minute of 1s samples, however many the 500ms-sized cap allows.
+        let raw = history.query(HistorySeries::Cpu, None, None, Resolution::Raw);
+        assert_eq!(raw.points.len(), 60);
+        assert_eq!(raw.points[0].ts_ms, 1_740_000);
+    }
+
+    #[test]
+    fn retention_is_clamped_to_its_maximums() {
+        let mut history = filled(RetentionConfig::default());
+        history.set_retention(RetentionConfig {
+            raw_minutes: u64::MAX,
+            ten_second_hours: u64::MAX / 2,
+            one_minute_days: 0,
+        });
+        let retention = history.retention();
+        assert_eq!(retention.raw_minutes, super::MAX_RAW_MINUTES);
+        assert_eq!(retention.ten_second_hours, super::MAX_TEN_SECOND_HOURS);
+        assert_eq!(retention.one_minute_days, 1);
+        // Growing the window keeps what is there without reserving a day of points.
+        let raw = history.query(HistorySeries::Cpu, None, None, Resolution::Raw);
+        assert_eq!(raw.points.len(), 600);
+    }
+}
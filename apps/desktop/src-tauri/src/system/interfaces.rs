@@ -0,0 +1,37 @@
+use std::{collections::HashMap, fs, path::Path};
+
+pub fn read_interface_indexes(sys_root: &Path) -> HashMap<String, u32> {
+    let Ok(entries) = fs::read_dir(sys_root.join("class/net")) else {
+        return HashMap::new();
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let index = fs::read_to_string(entry.path().join("ifindex")).ok()?;
+            Some((
+                entry.file_name().to_string_lossy().to_string(),
+                index.trim().parse().ok()?,
+            ))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::test_support::TempDir;
+
+    #[test]
+    fn read_interface_indexes_maps_names_to_ifindex() {
+        let root = TempDir::new("ifindex");
+        fs::create_dir_all(root.join("class/net/wg0")).unwrap();
+        fs::write(root.join("class/net/wg0/ifindex"), "17\n").unwrap();
+        fs::create_dir_all(root.join("class/net/broken")).unwrap();
+
+        let indexes = super::read_interface_indexes(&root);
+
+        assert_eq!(indexes.get("wg0"), Some(&17));
+        assert!(!indexes.contains_key("broken"));
+    }
+}
@@ -0,0 +1,179 @@
+use std::collections::HashMap;
+
+use crate::system::{
+    clock::SampleTime,
+    delta::rate_per_sec,
+    models::{InterruptRates, IrqRate, IrqRateTable},
+};
+
+#[derive(Debug, Clone)]
+pub struct IrqCounts {
+    pub source: String,
+    pub label: String,
+    pub per_cpu: Vec<u64>,
+    pub total: u64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct IrqTable {
+    pub cpus: Vec<usize>,
+    pub rows: Vec<IrqCounts>,
+}
+
+// /proc/interrupts and /proc/softirqs share a layout: a `CPUn` header, then
+// one `source:` row per line with a count per CPU and an optional description.
+pub fn parse_irq_table(input: &str) -> IrqTable {
+    let mut lines = input.lines();
+    let cpus: Vec<usize> = lines
+        .next()
+        .unwrap_or("")
+        .split_whitespace()
+        .filter_map(|column| column.strip_prefix("CPU")?.parse().ok())
+        .collect();
+
+    let rows = lines
+        .filter_map(|line| {
+            let (source, rest) = line.split_once(':')?;
+            let mut parts = rest.split_whitespace().peekable();
+            let mut per_cpu = Vec::with_capacity(cpus.len());
+            while per_cpu.len() < cpus.len() {
+                let Some(value) = parts.peek().and_then(|part| part.parse::<u64>().ok()) else {
+                    break;
+                };
+                per_cpu.push(value);
+                parts.next();
+            }
+            let total = per_cpu.iter().sum();
+            // ERR and MIS carry a single system-wide count rather than one per
+            // CPU, so they get no per-CPU breakdown at all.
+            if per_cpu.len() < cpus.len() {
+                per_cpu.clear();
+            }
+            Some(IrqCounts {
+                source: source.trim().to_string(),
+                label: parts.collect::<Vec<_>>().join(" "),
+                per_cpu,
+                total,
+            })
+        })
+        .collect();
+
+    IrqTable { cpus, rows }
+}
+
+fn rate_table(prev: Option<&IrqTable>, next: &IrqTable, delta_ms: f64) -> IrqRateTable {
+    let prev_rows: HashMap<&str, &IrqCounts> = prev
+        .map(|table| table.rows.iter().map(|row| (row.source.as_str(), row)).collect())
+        .unwrap_or_default();
+    // Match columns by CPU id so a core going offline does not shift the others.
+    let prev_column = |cpu: usize| prev?.cpus.iter().position(|&id| id == cpu);
+
+    let rows = next
+        .rows
+        .iter()
+        .map(|row| {
+            let prev_row = prev_rows.get(row.source.as_str());
+            let per_cpu: Vec<f64> = next
+                .cpus
+                .iter()
+                .zip(&row.per_cpu)
+                .map(|(&cpu, &count)| {
+                    let prev_count = prev_row.zip(prev_column(cpu)).and_then(|(prev, i)| prev.per_cpu.get(i));
+                    prev_count.map_or(0.0, |&prev| rate_per_sec(prev, count, delta_ms))
+                })
+                .collect();
+            let total_per_sec = if row.per_cpu.is_empty() {
+                prev_row.map_or(0.0, |prev| rate_per_sec(prev.total, row.total, delta_ms))
+            } else {
+                per_cpu.iter().sum()
+            };
+            IrqRate {
+                source: row.source.clone(),
+                label: row.label.clone(),
+                per_cpu,
+                total_per_sec,
+            }
+        })
+        .collect();
+
+    IrqRateTable {
+        cpus: next.cpus.clone(),
+        rows,
+    }
+}
+
+#[derive(Default)]
+pub struct InterruptMonitor {
+    last: Option<(SampleTime, IrqTable, IrqTable)>,
+}
+
+impl InterruptMonitor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn ingest(&mut self, interrupts_raw: &str, softirqs_raw: &str, now: SampleTime) -> InterruptRates {
+        let interrupts = parse_irq_table(interrupts_raw);
+        let softirqs = parse_irq_table(softirqs_raw);
+        let prev = self.last.take();
+        let interval_ms = prev.as_ref().map_or(0, |(at, _, _)| now.since(at).running_ms);
+        let delta_ms = interval_ms.max(1) as f64;
+
+        let rates = InterruptRates {
+            interval_ms,
+            interrupts: rate_table(prev.as_ref().map(|(_, table, _)| table), &interrupts, delta_ms),
+            softirqs: rate_table(prev.as_ref().map(|(_, _, table)| table), &softirqs, delta_ms),
+        };
+        self.last = Some((now, interrupts, softirqs));
+        rates
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::InterruptMonitor;
+    use crate::system::clock::SampleTime;
+
+    const INTERRUPTS: &str = "           CPU0       CPU1
+  0:         36          0   IO-APIC   2-edge      timer
+ 24:       1000         10   PCI-MSI 524288-edge      enp3s0-rx-0
+NMI:          0          0   Non-maskable interrupts
+ERR:          7
+";
+
+    #[test]
+    fn parse_irq_table_reads_counts_and_labels() {
+        let table = super::parse_irq_table(INTERRUPTS);
+        assert_eq!(table.cpus, vec![0, 1]);
+        assert_eq!(table.rows.len(), 4);
+        assert_eq!(table.rows[1].source, "24");
+        assert_eq!(table.rows[1].per_cpu, vec![1000, 10]);
+        assert_eq!(table.rows[1].label, "PCI-MSI 524288-edge enp3s0-rx-0");
+        assert_eq!(table.rows[1].total, 1010);
+        assert!(table.rows[3].per_cpu.is_empty());
+        assert_eq!(table.rows[3].total, 7);
+        assert_eq!(table.rows[3].label, "");
+    }
+
+    #[test]
+    fn monitor_reports_per_cpu_rates() {
+        let softirqs = |rx: u64| format!("                    CPU0       CPU1\n      NET_RX:  {rx}          5\n");
+        let mut monitor = InterruptMonitor::new();
+        let first = monitor.ingest(INTERRUPTS, &softirqs(100), SampleTime::at(1_000));
+        assert_eq!(first.interval_ms, 0);
+        assert_eq!(first.interrupts.rows[1].total_per_sec, 0.0);
+
+        let next = INTERRUPTS
+            .replace("1000         10", "3000         10")
+            .replace("ERR:          7", "ERR:          9");
+        let rates = monitor.ingest(&next, &softirqs(600), SampleTime::at(3_000));
+        assert_eq!(rates.interval_ms, 2_000);
+        let nic = &rates.interrupts.rows[1];
+        assert!((nic.per_cpu[0] - 1_000.0).abs() < 0.01);
+        assert_eq!(nic.per_cpu[1], 0.0);
+        assert!((rates.softirqs.rows[0].total_per_sec - 250.0).abs() < 0.01);
+        let err = &rates.interrupts.rows[3];
+        assert!(err.per_cpu.is_empty());
+        assert!((err.total_per_sec - 1.0).abs() < 0.01);
+    }
+}
@@ -6,3 +6,15 @@ pub mod cache;
 pub mod collector;
 pub mod processes;
 pub mod signal;
+pub mod sampler;
+pub mod block;
+pub mod filesystems;
+pub mod sensors;
+pub mod cpufreq;
+pub mod power;
+pub mod interrupts;
+pub mod clock;
+pub mod interfaces;
+pub mod history;
+pub mod store;
+pub mod export;
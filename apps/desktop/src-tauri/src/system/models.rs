@@ -1,4 +1,4 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize)]
 pub struct MetricPoint {
@@ -6,8 +6,390 @@ pub struct MetricPoint {
     pub value: f64,
 }
 
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct CpuModes<T> {
+    pub user: T,
+    pub nice: T,
+    pub system: T,
+    pub idle: T,
+    pub iowait: T,
+    pub irq: T,
+    pub softirq: T,
+    pub steal: T,
+    pub guest: T,
+    pub guest_nice: T,
+}
+
+impl<T> CpuModes<T> {
+    pub fn from_fn(mut f: impl FnMut() -> T) -> Self {
+        Self {
+            user: f(),
+            nice: f(),
+            system: f(),
+            idle: f(),
+            iowait: f(),
+            irq: f(),
+            softirq: f(),
+            steal: f(),
+            guest: f(),
+            guest_nice: f(),
+        }
+    }
+
+    pub fn map<U>(&self, mut f: impl FnMut(&T) -> U) -> CpuModes<U> {
+        CpuModes {
+            user: f(&self.user),
+            nice: f(&self.nice),
+            system: f(&self.system),
+            idle: f(&self.idle),
+            iowait: f(&self.iowait),
+            irq: f(&self.irq),
+            softirq: f(&self.softirq),
+            steal: f(&self.steal),
+            guest: f(&self.guest),
+            guest_nice: f(&self.guest_nice),
+        }
+    }
+
+    pub fn zip_mut<U>(&mut self, other: &CpuModes<U>, mut f: impl FnMut(&mut T, &U)) {
+        f(&mut self.user, &other.user);
+        f(&mut self.nice, &other.nice);
+        f(&mut self.system, &other.system);
+        f(&mut self.idle, &other.idle);
+        f(&mut self.iowait, &other.iowait);
+        f(&mut self.irq, &other.irq);
+        f(&mut self.softirq, &other.softirq);
+        f(&mut self.steal, &other.steal);
+        f(&mut self.guest, &other.guest);
+        f(&mut self.guest_nice, &other.guest_nice);
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CoreFrequency {
+    pub cur_khz: u64,
+    pub min_khz: u64,
+    pub max_khz: u64,
+    pub governor: String,
+    pub energy_performance_preference: Option<String>,
+    pub history: Vec<MetricPoint>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CoreUsage {
+    pub cpu: usize,
+    pub percent: f32,
+    pub history: Vec<MetricPoint>,
+    pub modes: CpuModes<f32>,
+    pub mode_history: CpuModes<Vec<MetricPoint>>,
+    pub frequency: Option<CoreFrequency>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NetInterfaceUsage {
+    pub name: String,
+    pub rx_bps: f64,
+    pub tx_bps: f64,
+    pub rx_pps: f64,
+    pub tx_pps: f64,
+    pub rx_errors_ps: f64,
+    pub tx_errors_ps: f64,
+    pub rx_drops_ps: f64,
+    pub tx_drops_ps: f64,
+    pub rx_fifo_ps: f64,
+    pub tx_fifo_ps: f64,
+    pub rx_history: Vec<MetricPoint>,
+    pub tx_history: Vec<MetricPoint>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BlockDeviceKind {
+    Disk,
+    Partition,
+    DeviceMapper,
+    Virtual,
+    Optical,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DiskUsage {
+    pub name: String,
+    pub kind: BlockDeviceKind,
+    pub label: Option<String>,
+    pub parent: Option<String>,
+    pub read_bps: f64,
+    pub write_bps: f64,
+    pub read_iops: f64,
+    pub write_iops: f64,
+    pub busy_percent: f64,
+    pub avg_await_ms: f64,
+    pub in_flight: u64,
+    pub read_history: Vec<MetricPoint>,
+    pub write_history: Vec<MetricPoint>,
+    pub busy_history: Vec<MetricPoint>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LoadUsage {
+    pub load1: f64,
+    pub load5: f64,
+    pub load15: f64,
+    pub runnable_tasks: u64,
+    pub total_tasks: u64,
+    pub procs_running: u64,
+    pub procs_blocked: u64,
+    pub forks_per_sec: f64,
+    pub ctxt_per_sec: f64,
+    pub intr_per_sec: f64,
+    pub softirq_per_sec: f64,
+    pub load1_history: Vec<MetricPoint>,
+    pub load5_history: Vec<MetricPoint>,
+    pub load15_history: Vec<MetricPoint>,
+    pub procs_running_history: Vec<MetricPoint>,
+    pub procs_blocked_history: Vec<MetricPoint>,
+    pub forks_history: Vec<MetricPoint>,
+    pub ctxt_history: Vec<MetricPoint>,
+    pub intr_history: Vec<MetricPoint>,
+    pub softirq_history: Vec<MetricPoint>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PressureStats {
+    pub some_avg10: f64,
+    pub some_avg60: f64,
+    pub some_avg300: f64,
+    pub some_stall_percent: f64,
+    pub full_avg10: f64,
+    pub full_avg60: f64,
+    pub full_avg300: f64,
+    pub full_stall_percent: f64,
+    pub some_history: Vec<MetricPoint>,
+    pub full_history: Vec<MetricPoint>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PressureUsage {
+    pub supported: bool,
+    pub cpu: PressureStats,
+    pub memory: PressureStats,
+    pub io: PressureStats,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SensorKind {
+    Temperature,
+    Fan,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SensorUsage {
+    pub id: String,
+    pub chip: String,
+    pub label: String,
+    pub kind: SensorKind,
+    pub value: f64,
+    pub max: Option<f64>,
+    pub crit: Option<f64>,
+    pub history: Vec<MetricPoint>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BatteryUsage {
+    pub name: String,
+    pub status: String,
+    pub capacity_percent: Option<f64>,
+    pub energy_now_wh: Option<f64>,
+    pub energy_full_wh: Option<f64>,
+    pub energy_full_design_wh: Option<f64>,
+    pub wear_percent: Option<f64>,
+    pub power_now_w: Option<f64>,
+    pub time_to_empty_secs: Option<u64>,
+    pub discharge_history: Vec<MetricPoint>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PowerUsage {
+    pub ac_online: Option<bool>,
+    pub batteries: Vec<BatteryUsage>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MemoryBreakdown {
+    pub total_bytes: u64,
+    pub free_bytes: u64,
+    pub available_bytes: u64,
+    pub anon_bytes: u64,
+    pub cached_bytes: u64,
+    pub shmem_bytes: u64,
+    pub buffers_bytes: u64,
+    pub slab_reclaimable_bytes: u64,
+    pub slab_unreclaimable_bytes: u64,
+    pub kernel_stack_bytes: u64,
+    pub page_tables_bytes: u64,
+    pub hugetlb_bytes: u64,
+    pub other_bytes: u64,
+    pub mapped_bytes: u64,
+    pub dirty_bytes: u64,
+    pub writeback_bytes: u64,
+    pub committed_as_bytes: u64,
+    pub commit_limit_bytes: u64,
+    pub hugepages_total: u64,
+    pub hugepages_free: u64,
+    pub hugepage_size_bytes: u64,
+    pub zswap_bytes: u64,
+    pub zswapped_bytes: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct VmActivity {
+    pub pgfault_ps: f64,
+    pub pgmajfault_ps: f64,
+    pub pswpin_ps: f64,
+    pub pswpout_ps: f64,
+    pub pgscan_ps: f64,
+    pub pgsteal_ps: f64,
+    pub compact_stall_ps: f64,
+    pub oom_kill_total: u64,
+    pub oom_kills: u64,
+    pub pgfault_history: Vec<MetricPoint>,
+    pub pgmajfault_history: Vec<MetricPoint>,
+    pub pswpin_history: Vec<MetricPoint>,
+    pub pswpout_history: Vec<MetricPoint>,
+    pub pgscan_history: Vec<MetricPoint>,
+    pub pgsteal_history: Vec<MetricPoint>,
+    pub compact_stall_history: Vec<MetricPoint>,
+    pub oom_kill_history: Vec<MetricPoint>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct IrqRate {
+    pub source: String,
+    pub label: String,
+    pub per_cpu: Vec<f64>,
+    pub total_per_sec: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct IrqRateTable {
+    pub cpus: Vec<usize>,
+    pub rows: Vec<IrqRate>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct InterruptRates {
+    pub interval_ms: u64,
+    pub interrupts: IrqRateTable,
+    pub softirqs: IrqRateTable,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HistorySeries {
+    Cpu,
+    Ram,
+    DiskRead,
+    DiskWrite,
+    NetRx,
+    NetTx,
+}
+
+impl HistorySeries {
+    pub const ALL: [HistorySeries; 6] = [
+        HistorySeries::Cpu,
+        HistorySeries::Ram,
+        HistorySeries::DiskRead,
+        HistorySeries::DiskWrite,
+        HistorySeries::NetRx,
+        HistorySeries::NetTx,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            HistorySeries::Cpu => "cpu",
+            HistorySeries::Ram => "ram",
+            HistorySeries::DiskRead => "disk_read",
+            HistorySeries::DiskWrite => "disk_write",
+            HistorySeries::NetRx => "net_rx",
+            HistorySeries::NetTx => "net_tx",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|series| series.as_str() == name)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Resolution {
+    Auto,
+    Raw,
+    TenSeconds,
+    OneMinute,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct RollupPoint {
+    pub ts_ms: i64,
+    pub min: f64,
+    pub avg: f64,
+    pub max: f64,
+}
+
+impl From<&MetricPoint> for RollupPoint {
+    fn from(point: &MetricPoint) -> Self {
+        Self {
+            ts_ms: point.ts_ms,
+            min: point.value,
+            avg: point.value,
+            max: point.value,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HistoryRange {
+    pub series: HistorySeries,
+    pub resolution: Resolution,
+    pub points: Vec<RollupPoint>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    Csv,
+    Jsonl,
+    Influx,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExportRequest {
+    pub path: String,
+    pub format: ExportFormat,
+    pub series: Vec<String>,
+    pub start_ms: Option<i64>,
+    pub end_ms: Option<i64>,
+    pub resolution: Option<Resolution>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportSummary {
+    pub path: String,
+    pub series: usize,
+    pub rows: u64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct HistoryGap {
+    pub start_ms: i64,
+    pub end_ms: i64,
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct MetricsSnapshot {
+    pub ts_ms: i64,
     pub cpu_percent: f32,
     pub ram_used_bytes: u64,
     pub ram_total_bytes: u64,
@@ -23,13 +405,70 @@ pub struct MetricsSnapshot {
     pub disk_write_history: Vec<MetricPoint>,
     pub net_rx_history: Vec<MetricPoint>,
     pub net_tx_history: Vec<MetricPoint>,
+    // How far back the histories in a snapshot reach.
+    pub live_window_ms: i64,
+    pub cpu_modes: CpuModes<f32>,
+    pub cpu_mode_history: CpuModes<Vec<MetricPoint>>,
+    pub cores: Vec<CoreUsage>,
+    pub net_interfaces: Vec<NetInterfaceUsage>,
+    pub disks: Vec<DiskUsage>,
+    pub load: LoadUsage,
+    pub pressure: PressureUsage,
+    pub sensors: Vec<SensorUsage>,
+    pub power: PowerUsage,
+    pub memory: MemoryBreakdown,
+    pub vm: VmActivity,
+    pub gaps: Vec<HistoryGap>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FilesystemUsage {
+    pub mount_point: String,
+    pub source: String,
+    pub fs_type: String,
+    pub total_bytes: u64,
+    pub used_bytes: u64,
+    pub free_bytes: u64,
+    pub available_bytes: u64,
+    pub used_percent: f64,
+    pub inodes_total: u64,
+    pub inodes_used: u64,
+    pub inodes_free: u64,
+    pub inodes_used_percent: f64,
+    pub history: Option<Vec<MetricPoint>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TerminateStage {
+    TermSent,
+    Waiting,
+    KillSent,
+    Exited,
+    Killed,
+    StillRunning,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TerminateEvent {
+    pub pid: i32,
+    pub start_time: u64,
+    pub stage: TerminateStage,
+    pub elapsed_ms: u64,
+    pub grace_ms: u64,
+    // proc(5) state letter, set when the process outlived SIGKILL.
+    pub process_state: Option<char>,
+    pub error: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize)]
 pub struct ProcessRow {
     pub pid: i32,
+    pub start_time: u64,
     pub user: String,
     pub command: String,
     pub cpu_percent: f32,
+    pub cpu_core_percent: f32,
     pub mem_percent: f32,
 }
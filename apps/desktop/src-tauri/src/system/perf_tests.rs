@@ -4,7 +4,7 @@ mod tests {
 
     #[test]
     fn collector_stays_within_history_budget() {
-        let collector = SystemCollector::new_for_tests(500);
+        let collector = SystemCollector::new_for_tests();
         let snapshot = collector.snapshot_len();
         assert_eq!(snapshot, 0);
     }
@@ -0,0 +1,92 @@
+use std::{collections::HashMap, fs, path::Path};
+
+#[derive(Debug, Clone, Default)]
+pub struct PowerSupply {
+    pub name: String,
+    pub kind: String,
+    pub online: Option<bool>,
+    pub status: Option<String>,
+    pub capacity_percent: Option<f64>,
+    pub energy_now_uwh: Option<f64>,
+    pub energy_full_uwh: Option<f64>,
+    pub energy_full_design_uwh: Option<f64>,
+    pub power_now_uw: Option<f64>,
+}
+
+impl PowerSupply {
+    pub fn is_battery(&self) -> bool {
+        self.kind == "Battery"
+    }
+
+    pub fn is_mains(&self) -> bool {
+        self.kind == "Mains"
+    }
+}
+
+pub fn parse_power_supply_uevent(input: &str) -> Option<PowerSupply> {
+    let fields: HashMap<&str, &str> = input
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("POWER_SUPPLY_")?.split_once('='))
+        .collect();
+    let text = |key: &str| fields.get(key).map(|value| value.trim().to_string());
+    // Some drivers report negative current/power while discharging.
+    let number = |key: &str| fields.get(key)?.trim().parse::<f64>().ok().map(f64::abs);
+    let voltage_v = number("VOLTAGE_NOW").map(|uv| uv / 1_000_000.0);
+    // Charge-based gauges report µAh/µA; convert through the current voltage.
+    let via_voltage = |direct_key: &str, charge_key: &str| {
+        number(direct_key).or_else(|| Some(number(charge_key)? * voltage_v?))
+    };
+
+    Some(PowerSupply {
+        name: text("NAME")?,
+        kind: text("TYPE")?,
+        online: number("ONLINE").map(|value| value > 0.0),
+        status: text("STATUS"),
+        capacity_percent: number("CAPACITY"),
+        energy_now_uwh: via_voltage("ENERGY_NOW", "CHARGE_NOW"),
+        energy_full_uwh: via_voltage("ENERGY_FULL", "CHARGE_FULL"),
+        energy_full_design_uwh: via_voltage("ENERGY_FULL_DESIGN", "CHARGE_FULL_DESIGN"),
+        power_now_uw: via_voltage("POWER_NOW", "CURRENT_NOW"),
+    })
+}
+
+pub fn read_power_supply_uevents(sys_root: &Path) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(sys_root.join("class/power_supply")) else {
+        return Vec::new();
+    };
+    let mut uevents: Vec<(String, String)> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let raw = fs::read_to_string(entry.path().join("uevent")).ok()?;
+            Some((entry.file_name().to_string_lossy().to_string(), raw))
+        })
+        .collect();
+    uevents.sort();
+    uevents.into_iter().map(|(_, raw)| raw).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn parse_power_supply_uevent_reads_energy_battery() {
+        let raw = "POWER_SUPPLY_NAME=BAT0\nPOWER_SUPPLY_TYPE=Battery\nPOWER_SUPPLY_STATUS=Discharging\nPOWER_SUPPLY_POWER_NOW=9500000\nPOWER_SUPPLY_ENERGY_FULL_DESIGN=57000000\nPOWER_SUPPLY_ENERGY_FULL=51300000\nPOWER_SUPPLY_ENERGY_NOW=28500000\nPOWER_SUPPLY_CAPACITY=55\n";
+        let battery = super::parse_power_supply_uevent(raw).expect("battery");
+        assert!(battery.is_battery());
+        assert_eq!(battery.status.as_deref(), Some("Discharging"));
+        assert_eq!(battery.capacity_percent, Some(55.0));
+        assert_eq!(battery.energy_now_uwh, Some(28_500_000.0));
+        assert_eq!(battery.power_now_uw, Some(9_500_000.0));
+    }
+
+    #[test]
+    fn parse_power_supply_uevent_converts_charge_battery() {
+        let raw = "POWER_SUPPLY_NAME=BAT1\nPOWER_SUPPLY_TYPE=Battery\nPOWER_SUPPLY_VOLTAGE_NOW=12000000\nPOWER_SUPPLY_CURRENT_NOW=-500000\nPOWER_SUPPLY_CHARGE_NOW=2000000\n";
+        let battery = super::parse_power_supply_uevent(raw).expect("battery");
+        assert_eq!(battery.energy_now_uwh, Some(24_000_000.0));
+        assert_eq!(battery.power_now_uw, Some(6_000_000.0));
+
+        let ac = super::parse_power_supply_uevent("POWER_SUPPLY_NAME=AC\nPOWER_SUPPLY_TYPE=Mains\nPOWER_SUPPLY_ONLINE=1\n").unwrap();
+        assert!(ac.is_mains());
+        assert_eq!(ac.online, Some(true));
+    }
+}
@@ -1,4 +1,4 @@
-use crate::system::models::ProcessRow;
+use crate::system::{cache::ProcessCache, clock::SampleTime, delta::counter_delta, models::ProcessRow};
 use serde::Deserialize;
 
 #[derive(Debug, Clone, Deserialize)]
@@ -10,6 +10,76 @@ pub struct ProcessQuery {
     pub offset: Option<usize>,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProcStat {
+    pub state: char,
+    pub utime: u64,
+    pub stime: u64,
+    pub start_time: u64,
+    pub rss_pages: u64,
+}
+
+// The comm field may contain spaces and parentheses, so fields are counted
+// from the last ')'; field N in proc(5) numbering sits at index N - 3.
+pub fn parse_proc_stat(raw: &str) -> Option<ProcStat> {
+    let rparen = raw.rfind(')')?;
+    let cols: Vec<&str> = raw.get(rparen + 2..)?.split_whitespace().collect();
+    let field = |n: usize| cols.get(n - 3)?.parse::<u64>().ok();
+    Some(ProcStat {
+        state: cols.first()?.chars().next()?,
+        utime: field(14)?,
+        stime: field(15)?,
+        start_time: field(22)?,
+        rss_pages: field(24)?,
+    })
+}
+
+pub struct ProcessSampler {
+    hz: f64,
+    cache: ProcessCache,
+    last_time: Option<SampleTime>,
+}
+
+impl ProcessSampler {
+    pub fn new(hz: f64) -> Self {
+        Self {
+            hz: hz.max(1.0),
+            cache: ProcessCache::default(),
+            last_time: None,
+        }
+    }
+
+    // Fills in CPU% over the time since the previous call. `cpu_core_percent`
+    // is top-style (100 per busy core), `cpu_percent` is that spread over all
+    // online CPUs. PIDs missing from `rows` are forgotten.
+    pub fn sample(&mut self, rows: &mut [ProcessRow], stats: &[ProcStat], now: SampleTime, cpus: usize) {
+        let now_ms = now.wall_ms as i64;
+        let prev = self.last_time.replace(now);
+        let cpus = cpus.max(1) as f64;
+
+        for (row, stat) in rows.iter_mut().zip(stats) {
+            let jiffies = stat.utime + stat.stime;
+            let baseline = self
+                .cache
+                .upsert_with_jiffies(row.pid, row.command.clone(), now_ms, stat.start_time, jiffies);
+            let started_ms = (stat.start_time as f64 / self.hz * 1_000.0) as u64;
+            let (busy, over_ms) = match (prev, baseline) {
+                (Some(prev), Some(before)) => (counter_delta(before, jiffies), now.since(&prev).running_ms),
+                // Started (or took over a recycled PID) since the last round, so
+                // everything it has used falls inside this interval.
+                (Some(prev), None) if started_ms >= prev.boot_ms => (jiffies, now.since(&prev).running_ms),
+                // Nothing to diff against yet: average over its lifetime like ps(1).
+                _ => (jiffies, now.boot_ms.saturating_sub(started_ms)),
+            };
+
+            let core_percent = (busy as f64 / self.hz * 100_000.0 / over_ms.max(1) as f64).min(100.0 * cpus);
+            row.cpu_core_percent = core_percent as f32;
+            row.cpu_percent = (core_percent / cpus) as f32;
+        }
+        self.cache.evict_unseen(now_ms);
+    }
+}
+
 pub struct ProcessService {
     rows: Vec<ProcessRow>,
 }
@@ -58,8 +128,63 @@ impl ProcessService {
 
 #[cfg(test)]
 mod tests {
-    use super::{ProcessQuery, ProcessService};
-    use crate::system::models::ProcessRow;
+    use super::{parse_proc_stat, ProcStat, ProcessQuery, ProcessSampler, ProcessService};
+    use crate::system::{clock::SampleTime, models::ProcessRow};
+
+    fn row(pid: i32) -> ProcessRow {
+        ProcessRow {
+            pid,
+            start_time: 0,
+            user: "alice".into(),
+            command: "stress".into(),
+            cpu_percent: 0.0,
+            cpu_core_percent: 0.0,
+            mem_percent: 0.0,
+        }
+    }
+
+    fn stat(start_time: u64, jiffies: u64) -> ProcStat {
+        ProcStat {
+            state: 'S',
+            utime: jiffies,
+            stime: 0,
+            start_time,
+            rss_pages: 0,
+        }
+    }
+
+    #[test]
+    fn parse_proc_stat_counts_fields_after_comm() {
+        let raw = "4242 (tmux: server) S 1 4242 4242 0 -1 4194560 1 0 0 0 70 30 0 0 20 0 1 0 123456 8192 512 18446744073709551615";
+        let stat = parse_proc_stat(raw).unwrap();
+        assert_eq!(stat.state, 'S');
+        assert_eq!((stat.utime, stat.stime), (70, 30));
+        assert_eq!(stat.start_time, 123_456);
+        assert_eq!(stat.rss_pages, 512);
+        assert_eq!(parse_proc_stat("1 (init) S 0"), None);
+    }
+
+    #[test]
+    fn sampler_reports_interval_cpu_and_detects_pid_reuse() {
+        // 100 Hz, 4 CPUs, rounds 1s apart at boot times 100s and 101s.
+        let mut sampler = ProcessSampler::new(100.0);
+        let mut rows = vec![row(10), row(20)];
+        // Both started at boot 0s and used 10s of CPU: 10% of one core over 100s.
+        sampler.sample(&mut rows, &[stat(0, 1_000), stat(0, 1_000)], SampleTime::at(100_000), 4);
+        assert!((rows[0].cpu_core_percent - 10.0).abs() < 0.01);
+
+        // pid 10 burned a full core; pid 20 exited and pid 30 started mid-interval.
+        let mut rows = vec![row(10), row(30)];
+        sampler.sample(&mut rows, &[stat(0, 1_100), stat(10_050, 50)], SampleTime::at(101_000), 4);
+        assert!((rows[0].cpu_core_percent - 100.0).abs() < 0.01);
+        assert!((rows[0].cpu_percent - 25.0).abs() < 0.01);
+        assert!((rows[1].cpu_core_percent - 50.0).abs() < 0.01);
+
+        // pid 10 was recycled by a new process between rounds.
+        let mut rows = vec![row(10)];
+        sampler.sample(&mut rows, &[stat(10_150, 20)], SampleTime::at(102_000), 4);
+        assert!((rows[0].cpu_core_percent - 20.0).abs() < 0.01);
+    }
 
     #[test]
     fn list_processes_supports_search_and_cpu_sort() {
@@ -67,16 +192,20 @@ mod tests {
         service.seed_for_tests(vec![
             ProcessRow {
                 pid: 10,
+                start_time: 0,
                 user: "alice".into(),
                 command: "bash".into(),
                 cpu_percent: 1.0,
+                cpu_core_percent: 4.0,
                 mem_percent: 0.2,
             },
             ProcessRow {
                 pid: 22,
+                start_time: 0,
                 user: "bob".into(),
                 command: "chrome".into(),
                 cpu_percent: 35.0,
+                cpu_core_percent: 140.0,
                 mem_percent: 10.0,
             },
         ]);
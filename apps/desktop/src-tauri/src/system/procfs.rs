@@ -1,9 +1,130 @@
-#[derive(Debug, Clone, Copy)]
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Copy, Default)]
 pub struct CpuTotals {
+    pub user: u64,
+    pub nice: u64,
+    pub system: u64,
     pub idle: u64,
+    pub iowait: u64,
+    pub irq: u64,
+    pub softirq: u64,
+    pub steal: u64,
+    pub guest: u64,
+    pub guest_nice: u64,
     pub total: u64,
 }
 
+#[derive(Debug, Clone, Copy)]
+pub struct CoreTotals {
+    pub cpu: usize,
+    pub totals: CpuTotals,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct NetDevCounters {
+    pub name: String,
+    pub rx_bytes: u64,
+    pub rx_packets: u64,
+    pub rx_errs: u64,
+    pub rx_drop: u64,
+    pub rx_fifo: u64,
+    pub tx_bytes: u64,
+    pub tx_packets: u64,
+    pub tx_errs: u64,
+    pub tx_drop: u64,
+    pub tx_fifo: u64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DiskCounters {
+    pub name: String,
+    pub reads: u64,
+    pub read_sectors: u64,
+    pub read_ms: u64,
+    pub writes: u64,
+    pub write_sectors: u64,
+    pub write_ms: u64,
+    pub in_flight: u64,
+    pub io_ticks_ms: u64,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LoadAvg {
+    pub one: f64,
+    pub five: f64,
+    pub fifteen: f64,
+    pub runnable: u64,
+    pub total_tasks: u64,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StatCounters {
+    pub ctxt: u64,
+    pub intr: u64,
+    pub softirq: u64,
+    pub processes: u64,
+    pub procs_running: u64,
+    pub procs_blocked: u64,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PressureLine {
+    pub avg10: f64,
+    pub avg60: f64,
+    pub avg300: f64,
+    pub total_us: u64,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Pressure {
+    pub some: PressureLine,
+    pub full: Option<PressureLine>,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct VmStat {
+    pub pgfault: u64,
+    pub pgmajfault: u64,
+    pub pswpin: u64,
+    pub pswpout: u64,
+    pub pgscan: u64,
+    pub pgsteal: u64,
+    pub compact_stall: u64,
+    pub oom_kill: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemInfoValue {
+    Bytes(u64),
+    Count(u64),
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct MemInfoMap {
+    fields: BTreeMap<String, MemInfoValue>,
+}
+
+impl MemInfoMap {
+    pub fn get(&self, key: &str) -> Option<MemInfoValue> {
+        self.fields.get(key).copied()
+    }
+
+    pub fn bytes(&self, key: &str) -> u64 {
+        match self.get(key) {
+            Some(MemInfoValue::Bytes(value)) => value,
+            _ => 0,
+        }
+    }
+
+    pub fn count(&self, key: &str) -> u64 {
+        match self.get(key) {
+            Some(MemInfoValue::Count(value)) => value,
+            _ => 0,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct MemInfo {
     pub mem_total_kb: u64,
@@ -14,7 +135,88 @@ pub struct MemInfo {
 
 pub fn parse_cpu_totals(input: &str) -> Option<CpuTotals> {
     let cpu_line = input.lines().find(|l| l.starts_with("cpu "))?;
-    let nums: Vec<u64> = cpu_line
+    parse_cpu_line(cpu_line)
+}
+
+pub fn parse_core_totals(input: &str) -> Vec<CoreTotals> {
+    let mut cores: Vec<CoreTotals> = input
+        .lines()
+        .filter_map(|line| {
+            let label = line.split_whitespace().next()?;
+            let cpu = label.strip_prefix("cpu")?.parse::<usize>().ok()?;
+            let totals = parse_cpu_line(line)?;
+            Some(CoreTotals { cpu, totals })
+        })
+        .collect();
+    cores.sort_by_key(|core| core.cpu);
+    cores
+}
+
+pub fn parse_stat_counters(input: &str) -> StatCounters {
+    let mut counters = StatCounters::default();
+    for line in input.lines() {
+        let mut parts = line.split_whitespace();
+        let key = parts.next().unwrap_or("");
+        let value = parts.next().and_then(|v| v.parse::<u64>().ok()).unwrap_or(0);
+        // intr and softirq lines start with the grand total, then per-source counts.
+        match key {
+            "ctxt" => counters.ctxt = value,
+            "intr" => counters.intr = value,
+            "softirq" => counters.softirq = value,
+            "processes" => counters.processes = value,
+            "procs_running" => counters.procs_running = value,
+            "procs_blocked" => counters.procs_blocked = value,
+            _ => {}
+        }
+    }
+    counters
+}
+
+pub fn parse_loadavg(input: &str) -> Option<LoadAvg> {
+    let mut parts = input.split_whitespace();
+    let one = parts.next()?.parse::<f64>().ok()?;
+    let five = parts.next()?.parse::<f64>().ok()?;
+    let fifteen = parts.next()?.parse::<f64>().ok()?;
+    let (runnable, total_tasks) = parts.next()?.split_once('/')?;
+    Some(LoadAvg {
+        one,
+        five,
+        fifteen,
+        runnable: runnable.parse().ok()?,
+        total_tasks: total_tasks.parse().ok()?,
+    })
+}
+
+pub fn parse_pressure(input: &str) -> Option<Pressure> {
+    let mut some = None;
+    let mut full = None;
+
+    for line in input.lines() {
+        let mut parts = line.split_whitespace();
+        let kind = parts.next()?;
+        let mut parsed = PressureLine::default();
+        for field in parts {
+            let (key, value) = field.split_once('=')?;
+            match key {
+                "avg10" => parsed.avg10 = value.parse().ok()?,
+                "avg60" => parsed.avg60 = value.parse().ok()?,
+                "avg300" => parsed.avg300 = value.parse().ok()?,
+                "total" => parsed.total_us = value.parse().ok()?,
+                _ => {}
+            }
+        }
+        match kind {
+            "some" => some = Some(parsed),
+            "full" => full = Some(parsed),
+            _ => {}
+        }
+    }
+
+    Some(Pressure { some: some?, full })
+}
+
+fn parse_cpu_line(line: &str) -> Option<CpuTotals> {
+    let nums: Vec<u64> = line
         .split_whitespace()
         .skip(1)
         .filter_map(|v| v.parse::<u64>().ok())
@@ -22,9 +224,31 @@ pub fn parse_cpu_totals(input: &str) -> Option<CpuTotals> {
     if nums.len() < 4 {
         return None;
     }
-    let idle = nums[3];
-    let total = nums.iter().sum();
-    Some(CpuTotals { idle, total })
+    // Older kernels stop after idle/iowait/irq; missing columns read as zero.
+    let col = |i: usize| nums.get(i).copied().unwrap_or(0);
+    let mut cpu = CpuTotals {
+        user: col(0),
+        nice: col(1),
+        system: col(2),
+        idle: col(3),
+        iowait: col(4),
+        irq: col(5),
+        softirq: col(6),
+        steal: col(7),
+        guest: col(8),
+        guest_nice: col(9),
+        total: 0,
+    };
+    // guest and guest_nice are already accounted inside user and nice.
+    cpu.total = cpu.user
+        + cpu.nice
+        + cpu.system
+        + cpu.idle
+        + cpu.iowait
+        + cpu.irq
+        + cpu.softirq
+        + cpu.steal;
+    Some(cpu)
 }
 
 pub fn parse_meminfo(input: &str) -> Option<MemInfo> {
@@ -52,6 +276,116 @@ pub fn parse_meminfo(input: &str) -> Option<MemInfo> {
     })
 }
 
+pub fn parse_net_dev(input: &str) -> Vec<NetDevCounters> {
+    let mut interfaces = Vec::new();
+
+    for line in input.lines() {
+        let Some((iface, data)) = line.split_once(':') else {
+            continue;
+        };
+        let iface = iface.trim();
+        if iface == "lo" || iface.is_empty() {
+            continue;
+        }
+
+        let cols: Vec<u64> = data
+            .split_whitespace()
+            .map(|v| v.parse::<u64>().unwrap_or(0))
+            .collect();
+        if cols.len() < 16 {
+            continue;
+        }
+
+        interfaces.push(NetDevCounters {
+            name: iface.to_string(),
+            rx_bytes: cols[0],
+            rx_packets: cols[1],
+            rx_errs: cols[2],
+            rx_drop: cols[3],
+            rx_fifo: cols[4],
+            tx_bytes: cols[8],
+            tx_packets: cols[9],
+            tx_errs: cols[10],
+            tx_drop: cols[11],
+            tx_fifo: cols[12],
+        });
+    }
+
+    interfaces
+}
+
+pub fn parse_diskstats(input: &str) -> Vec<DiskCounters> {
+    input
+        .lines()
+        .filter_map(|line| {
+            let cols: Vec<&str> = line.split_whitespace().collect();
+            if cols.len() < 14 {
+                return None;
+            }
+            let num = |i: usize| cols[i].parse::<u64>().unwrap_or(0);
+            Some(DiskCounters {
+                name: cols[2].to_string(),
+                reads: num(3),
+                read_sectors: num(5),
+                read_ms: num(6),
+                writes: num(7),
+                write_sectors: num(9),
+                write_ms: num(10),
+                in_flight: num(11),
+                io_ticks_ms: num(12),
+            })
+        })
+        .collect()
+}
+
+pub fn parse_meminfo_map(input: &str) -> MemInfoMap {
+    let mut fields = BTreeMap::new();
+    for line in input.lines() {
+        let Some((key, rest)) = line.split_once(':') else {
+            continue;
+        };
+        let mut parts = rest.split_whitespace();
+        let Some(value) = parts.next().and_then(|v| v.parse::<u64>().ok()) else {
+            continue;
+        };
+        // HugePages_* are page counts; everything with a kB unit becomes bytes.
+        let value = match parts.next() {
+            Some("kB") => MemInfoValue::Bytes(value.saturating_mul(1024)),
+            _ => MemInfoValue::Count(value),
+        };
+        fields.insert(key.trim().to_string(), value);
+    }
+    MemInfoMap { fields }
+}
+
+fn is_reclaim_source(key: &str, prefix: &str) -> bool {
+    key.strip_prefix(prefix).is_some_and(|source| {
+        ["kswapd", "direct", "khugepaged"].iter().any(|name| source.starts_with(name)) && source != "direct_throttle"
+    })
+}
+
+pub fn parse_vmstat(input: &str) -> VmStat {
+    let mut vm = VmStat::default();
+    for line in input.lines() {
+        let mut parts = line.split_whitespace();
+        let key = parts.next().unwrap_or("");
+        let value = parts.next().and_then(|v| v.parse::<u64>().ok()).unwrap_or(0);
+        match key {
+            "pgfault" => vm.pgfault = value,
+            "pgmajfault" => vm.pgmajfault = value,
+            "pswpin" => vm.pswpin = value,
+            "pswpout" => vm.pswpout = value,
+            "compact_stall" => vm.compact_stall = value,
+            "oom_kill" => vm.oom_kill = value,
+            // Sum reclaim by source; the anon/file split counts the same pages again.
+            _ if is_reclaim_source(key, "pgscan_") => vm.pgscan = vm.pgscan.saturating_add(value),
+            _ if is_reclaim_source(key, "pgsteal_") => vm.pgsteal = vm.pgsteal.saturating_add(value),
+            _ => {}
+        }
+    }
+    vm
+}
+
 #[cfg(test)]
 mod tests {
     #[test]
@@ -71,4 +405,105 @@ mod tests {
         assert_eq!(cpu.idle, 22625563);
         assert_eq!(cpu.total, 22637015);
     }
+
+    #[test]
+    fn parse_cpu_totals_keeps_every_mode_column() {
+        let text = "cpu  500 20 300 9000 40 5 6 7 100 10\n";
+        let cpu = super::parse_cpu_totals(text).expect("cpu");
+        assert_eq!(cpu.user, 500);
+        assert_eq!(cpu.nice, 20);
+        assert_eq!(cpu.system, 300);
+        assert_eq!(cpu.iowait, 40);
+        assert_eq!(cpu.irq, 5);
+        assert_eq!(cpu.softirq, 6);
+        assert_eq!(cpu.steal, 7);
+        assert_eq!(cpu.guest, 100);
+        assert_eq!(cpu.guest_nice, 10);
+        assert_eq!(cpu.total, 9878);
+    }
+
+    #[test]
+    fn parse_core_totals_reads_each_online_core() {
+        // cpu1 is offline, so the kernel omits its line entirely.
+        let text = "cpu  30 0 10 160 0 0 0 0 0 0\ncpu0 10 0 5 80 0 0 0 0 0 0\ncpu2 20 0 5 80 0 0 0 0 0 0\nintr 12345 0 0\n";
+        let cores = super::parse_core_totals(text);
+        assert_eq!(cores.len(), 2);
+        assert_eq!(cores[0].cpu, 0);
+        assert_eq!(cores[0].totals.idle, 80);
+        assert_eq!(cores[1].cpu, 2);
+        assert_eq!(cores[1].totals.total, 105);
+    }
+
+    #[test]
+    fn parse_net_dev_reads_packet_error_and_drop_columns() {
+        let text = "Inter-|   Receive\n face |bytes\n    lo: 900 0 0 0 0 0 0 0 900 0 0 0 0 0 0 0\n  eth0: 1000 10 1 2 3 0 0 0 2000 20 4 5 6 0 0 0\n";
+        let ifaces = super::parse_net_dev(text);
+        assert_eq!(ifaces.len(), 1);
+        let eth0 = &ifaces[0];
+        assert_eq!(eth0.name, "eth0");
+        assert_eq!((eth0.rx_packets, eth0.rx_errs, eth0.rx_drop, eth0.rx_fifo), (10, 1, 2, 3));
+        assert_eq!((eth0.tx_bytes, eth0.tx_packets), (2000, 20));
+        assert_eq!((eth0.tx_errs, eth0.tx_drop, eth0.tx_fifo), (4, 5, 6));
+    }
+
+    #[test]
+    fn parse_diskstats_reads_io_counters() {
+        let text = "   8       0 sda 120 5 2048 300 80 2 1024 400 3 650 700 0 0 0 0 0 0\n";
+        let disks = super::parse_diskstats(text);
+        assert_eq!(disks.len(), 1);
+        let sda = &disks[0];
+        assert_eq!(sda.name, "sda");
+        assert_eq!((sda.reads, sda.read_sectors, sda.read_ms), (120, 2048, 300));
+        assert_eq!((sda.writes, sda.write_sectors, sda.write_ms), (80, 1024, 400));
+        assert_eq!((sda.in_flight, sda.io_ticks_ms), (3, 650));
+    }
+
+    #[test]
+    fn parse_loadavg_reads_averages_and_tasks() {
+        let load = super::parse_loadavg("0.52 1.25 2.50 3/842 123456\n").expect("loadavg");
+        assert!((load.one - 0.52).abs() < 1e-9);
+        assert!((load.fifteen - 2.5).abs() < 1e-9);
+        assert_eq!((load.runnable, load.total_tasks), (3, 842));
+    }
+
+    #[test]
+    fn parse_stat_counters_reads_task_lines() {
+        let text = "cpu  1 2 3 4\nintr 5000 36 9 0 0\nctxt 999\nbtime 1700000000\nprocesses 52311\nprocs_running 4\nprocs_blocked 2\nsoftirq 800 0 300 1 200\n";
+        let counters = super::parse_stat_counters(text);
+        assert_eq!((counters.ctxt, counters.intr, counters.softirq), (999, 5000, 800));
+        assert_eq!(counters.processes, 52311);
+        assert_eq!(counters.procs_running, 4);
+        assert_eq!(counters.procs_blocked, 2);
+    }
+
+    #[test]
+    fn parse_pressure_reads_some_and_full() {
+        let text = "some avg10=1.50 avg60=0.80 avg300=0.20 total=123456\nfull avg10=0.50 avg60=0.10 avg300=0.00 total=4567\n";
+        let pressure = super::parse_pressure(text).expect("pressure");
+        assert!((pressure.some.avg10 - 1.5).abs() < 1e-9);
+        assert_eq!(pressure.some.total_us, 123456);
+        assert_eq!(pressure.full.expect("full").total_us, 4567);
+        assert!(super::parse_pressure("").is_none());
+    }
+
+    #[test]
+    fn parse_meminfo_map_keeps_units_apart() {
+        let text = "MemTotal:       16384256 kB\nSReclaimable:     204800 kB\nHugePages_Total:       8\nHugepagesize:       2048 kB\n";
+        let map = super::parse_meminfo_map(text);
+        assert_eq!(map.bytes("SReclaimable"), 204800 * 1024);
+        assert_eq!(map.count("HugePages_Total"), 8);
+        assert_eq!(map.bytes("HugePages_Total"), 0);
+        assert_eq!(map.get("Hugepagesize"), Some(super::MemInfoValue::Bytes(2048 * 1024)));
+    }
+
+    #[test]
+    fn parse_vmstat_sums_reclaim_sources() {
+        let text = "pgfault 1000\npgmajfault 12\npswpin 5\npswpout 7\npgscan_kswapd 100\npgscan_direct 20\npgscan_anon 60\npgscan_file 60\npgscan_direct_throttle 4\npgsteal_kswapd 90\npgsteal_direct 10\ncompact_stall 3\noom_kill 1\n";
+        let vm = super::parse_vmstat(text);
+        assert_eq!((vm.pgfault, vm.pgmajfault), (1000, 12));
+        assert_eq!((vm.pswpin, vm.pswpout), (5, 7));
+        assert_eq!(vm.pgscan, 120);
+        assert_eq!(vm.pgsteal, 100);
+        assert_eq!((vm.compact_stall, vm.oom_kill), (3, 1));
+    }
 }
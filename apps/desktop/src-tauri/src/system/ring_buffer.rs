@@ -4,10 +4,12 @@ pub struct RingBuffer<T> {
 }
 
 impl<T: Clone> RingBuffer<T> {
+    // The cap can be large (a day of raw history), so storage grows as
+    // values arrive instead of being reserved up front.
     pub fn new(cap: usize) -> Self {
         Self {
             cap,
-            data: std::collections::VecDeque::with_capacity(cap),
+            data: std::collections::VecDeque::new(),
         }
     }
 
@@ -21,6 +23,35 @@ impl<T: Clone> RingBuffer<T> {
     pub fn snapshot(&self) -> Vec<T> {
         self.data.iter().cloned().collect()
     }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.data.iter()
+    }
+
+    pub fn first(&self) -> Option<&T> {
+        self.data.front()
+    }
+
+    // Walks back from the newest value, so the cost is the size of the tail.
+    pub fn tail_while(&self, mut keep: impl FnMut(&T) -> bool) -> Vec<T> {
+        let len = self.data.iter().rev().take_while(|value| keep(value)).count();
+        self.data.range(self.data.len() - len..).cloned().collect()
+    }
+
+    pub fn drop_front_while(&mut self, mut drop: impl FnMut(&T) -> bool) {
+        while self.data.front().is_some_and(&mut drop) {
+            self.data.pop_front();
+        }
+    }
+
+    pub fn resize(&mut self, cap: usize) {
+        // Shrinking keeps the newest values.
+        let cap = cap.max(1);
+        while self.data.len() > cap {
+            self.data.pop_front();
+        }
+        self.cap = cap;
+    }
 }
 
 #[cfg(test)]
@@ -36,4 +67,32 @@ mod tests {
         rb.push(4);
         assert_eq!(rb.snapshot(), vec![2, 3, 4]);
     }
+
+    #[test]
+    fn ring_buffer_resize_drops_oldest_points() {
+        let mut rb = RingBuffer::new(4);
+        for value in 1..=4 {
+            rb.push(value);
+        }
+        rb.resize(2);
+        assert_eq!(rb.snapshot(), vec![3, 4]);
+        rb.resize(3);
+        rb.push(5);
+        assert_eq!(rb.snapshot(), vec![3, 4, 5]);
+        assert_eq!(rb.first(), Some(&3));
+    }
+
+    #[test]
+    fn ring_buffer_tail_while_returns_only_the_newest_run() {
+        let mut rb = RingBuffer::new(4);
+        for value in 1..=6 {
+            rb.push(value);
+        }
+        assert_eq!(rb.tail_while(|value| *value > 4), vec![5, 6]);
+        assert_eq!(rb.tail_while(|value| *value > 6), Vec::<i32>::new());
+        assert_eq!(rb.tail_while(|_| true), vec![3, 4, 5, 6]);
+
+        rb.drop_front_while(|value| *value < 5);
+        assert_eq!(rb.snapshot(), vec![5, 6]);
+    }
 }
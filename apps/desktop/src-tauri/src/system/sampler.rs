@@ -0,0 +1,120 @@
+use std::{
+    sync::mpsc::{self, RecvTimeoutError, Sender},
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+enum SamplerCommand {
+    SetInterval(Duration),
+    Stop,
+}
+
+pub fn normalize_interval_ms(value: u64) -> u64 {
+    if value <= 500 {
+        return 500;
+    }
+    if value <= 1_000 {
+        return 1_000;
+    }
+    2_000
+}
+
+pub struct Sampler {
+    tx: Sender<SamplerCommand>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Sampler {
+    pub fn start<F>(interval: Duration, mut on_tick: F) -> Self
+    where
+        F: FnMut() + Send + 'static,
+    {
+        let (tx, rx) = mpsc::channel();
+        let handle = thread::spawn(move || {
+            let mut interval = interval;
+            let mut next_tick = Instant::now();
+            loop {
+                let wait = next_tick.saturating_duration_since(Instant::now());
+                match rx.recv_timeout(wait) {
+                    Ok(SamplerCommand::SetInterval(value)) => {
+                        interval = value;
+                        next_tick = Instant::now() + interval;
+                    }
+                    Ok(SamplerCommand::Stop) | Err(RecvTimeoutError::Disconnected) => break,
+                    Err(RecvTimeoutError::Timeout) => {
+                        on_tick();
+                        // Schedule from the previous deadline so a slow tick does not drift the cadence.
+                        next_tick += interval;
+                        let now = Instant::now();
+                        if next_tick < now {
+                            next_tick = now + interval;
+                        }
+                    }
+                }
+            }
+        });
+
+        Self {
+            tx,
+            handle: Some(handle),
+        }
+    }
+
+    pub fn set_interval(&mut self, interval: Duration) {
+        let _ = self.tx.send(SamplerCommand::SetInterval(interval));
+    }
+
+    pub fn stop(mut self) {
+        self.shutdown();
+    }
+
+    fn shutdown(&mut self) {
+        let _ = self.tx.send(SamplerCommand::Stop);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for Sampler {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        thread,
+        time::Duration,
+    };
+
+    use super::Sampler;
+
+    #[test]
+    fn sampler_ticks_until_stopped() {
+        let ticks = Arc::new(AtomicUsize::new(0));
+        let counter = ticks.clone();
+        let sampler = Sampler::start(Duration::from_millis(5), move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+        thread::sleep(Duration::from_millis(60));
+        sampler.stop();
+
+        let after_stop = ticks.load(Ordering::SeqCst);
+        assert!(after_stop >= 2);
+        thread::sleep(Duration::from_millis(20));
+        assert_eq!(ticks.load(Ordering::SeqCst), after_stop);
+    }
+
+    #[test]
+    fn normalize_interval_snaps_to_supported_values() {
+        assert_eq!(super::normalize_interval_ms(100), 500);
+        assert_eq!(super::normalize_interval_ms(700), 1_000);
+        assert_eq!(super::normalize_interval_ms(5_000), 2_000);
+    }
+}
@@ -0,0 +1,178 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::system::models::SensorKind;
+
+#[derive(Debug, Clone)]
+pub struct SensorReading {
+    pub id: String,
+    pub chip: String,
+    pub label: String,
+    pub kind: SensorKind,
+    pub value: f64,
+    pub max: Option<f64>,
+    pub crit: Option<f64>,
+}
+
+fn read_trimmed(path: &Path) -> Option<String> {
+    fs::read_to_string(path)
+        .ok()
+        .map(|raw| raw.trim().to_string())
+        .filter(|raw| !raw.is_empty())
+}
+
+fn read_number(path: &Path) -> Option<f64> {
+    read_trimmed(path)?.parse::<f64>().ok()
+}
+
+fn millidegrees(path: &Path) -> Option<f64> {
+    read_number(path).map(|value| value / 1000.0)
+}
+
+fn sorted_entries(dir: &Path, prefix: &str) -> Vec<PathBuf> {
+    let mut entries: Vec<PathBuf> = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|entry| entry.file_name().to_string_lossy().starts_with(prefix))
+                .map(|entry| entry.path())
+                .collect()
+        })
+        .unwrap_or_default();
+    entries.sort();
+    entries
+}
+
+fn read_hwmon_chip(dir: &Path, readings: &mut Vec<SensorReading>) {
+    let hwmon = dir.file_name().unwrap_or_default().to_string_lossy().to_string();
+    let chip = read_trimmed(&dir.join("name")).unwrap_or_else(|| hwmon.clone());
+
+    // Older drivers keep their attributes under device/ instead of the hwmon dir.
+    let attr_dir = if sorted_entries(dir, "temp").is_empty() && sorted_entries(dir, "fan").is_empty() {
+        dir.join("device")
+    } else {
+        dir.to_path_buf()
+    };
+
+    for input in sorted_entries(&attr_dir, "") {
+        let file = input.file_name().unwrap_or_default().to_string_lossy().to_string();
+        let Some(channel) = file.strip_suffix("_input") else {
+            continue;
+        };
+        let kind = if channel.starts_with("temp") {
+            SensorKind::Temperature
+        } else if channel.starts_with("fan") {
+            SensorKind::Fan
+        } else {
+            continue;
+        };
+
+        let attr = |suffix: &str| attr_dir.join(format!("{channel}_{suffix}"));
+        let (value, max, crit) = match kind {
+            SensorKind::Temperature => (
+                millidegrees(&input),
+                millidegrees(&attr("max")),
+                millidegrees(&attr("crit")),
+            ),
+            SensorKind::Fan => (read_number(&input), read_number(&attr("max")), None),
+        };
+        let Some(value) = value else {
+            continue;
+        };
+
+        readings.push(SensorReading {
+            id: format!("{hwmon}/{channel}"),
+            chip: chip.clone(),
+            label: read_trimmed(&attr("label")).unwrap_or_else(|| channel.to_string()),
+            kind,
+            value,
+            max,
+            crit,
+        });
+    }
+}
+
+fn read_thermal_zone(dir: &Path, readings: &mut Vec<SensorReading>) {
+    let zone = dir.file_name().unwrap_or_default().to_string_lossy().to_string();
+    let Some(value) = millidegrees(&dir.join("temp")) else {
+        return;
+    };
+
+    let mut max = None;
+    let mut crit = None;
+    for trip in sorted_entries(dir, "trip_point_") {
+        let file = trip.file_name().unwrap_or_default().to_string_lossy().to_string();
+        let Some(index) = file.strip_suffix("_type") else {
+            continue;
+        };
+        let temp = millidegrees(&dir.join(format!("{index}_temp")));
+        match read_trimmed(&trip).as_deref() {
+            Some("critical") => crit = crit.or(temp),
+            Some("hot") | Some("passive") => max = max.or(temp),
+            _ => {}
+        }
+    }
+
+    readings.push(SensorReading {
+        id: zone.clone(),
+        chip: "thermal".to_string(),
+        label: read_trimmed(&dir.join("type")).unwrap_or(zone),
+        kind: SensorKind::Temperature,
+        value,
+        max,
+        crit,
+    });
+}
+
+pub fn read_sensors(sys_root: &Path) -> Vec<SensorReading> {
+    let mut readings = Vec::new();
+    for chip in sorted_entries(&sys_root.join("class/hwmon"), "hwmon") {
+        read_hwmon_chip(&chip, &mut readings);
+    }
+    for zone in sorted_entries(&sys_root.join("class/thermal"), "thermal_zone") {
+        read_thermal_zone(&zone, &mut readings);
+    }
+    readings
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::{system::models::SensorKind, test_support::TempDir};
+
+    #[test]
+    fn read_sensors_collects_hwmon_and_thermal_zones() {
+        let root = TempDir::new("sensors");
+        let hwmon = root.join("class/hwmon/hwmon0");
+        fs::create_dir_all(&hwmon).unwrap();
+        fs::write(hwmon.join("name"), "coretemp\n").unwrap();
+        fs::write(hwmon.join("temp1_input"), "54000\n").unwrap();
+        fs::write(hwmon.join("temp1_label"), "Package id 0\n").unwrap();
+        fs::write(hwmon.join("temp1_max"), "80000\n").unwrap();
+        fs::write(hwmon.join("temp1_crit"), "100000\n").unwrap();
+        fs::write(hwmon.join("fan1_input"), "2100\n").unwrap();
+        let zone = root.join("class/thermal/thermal_zone0");
+        fs::create_dir_all(&zone).unwrap();
+        fs::write(zone.join("type"), "x86_pkg_temp\n").unwrap();
+        fs::write(zone.join("temp"), "61500\n").unwrap();
+        fs::write(zone.join("trip_point_0_type"), "critical\n").unwrap();
+        fs::write(zone.join("trip_point_0_temp"), "105000\n").unwrap();
+
+        let readings = super::read_sensors(&root);
+
+        assert_eq!(readings.len(), 3);
+        let fan = readings.iter().find(|r| r.kind == SensorKind::Fan).unwrap();
+        assert_eq!(fan.value, 2100.0);
+        let package = readings.iter().find(|r| r.label == "Package id 0").unwrap();
+        assert_eq!(package.chip, "coretemp");
+        assert_eq!(package.value, 54.0);
+        assert_eq!(package.max, Some(80.0));
+        assert_eq!(package.crit, Some(100.0));
+        let zone = readings.iter().find(|r| r.id == "thermal_zone0").unwrap();
+        assert_eq!(zone.label, "x86_pkg_temp");
+        assert_eq!(zone.crit, Some(105.0));
+    }
+}
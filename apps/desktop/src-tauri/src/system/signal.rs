@@ -1,9 +1,33 @@
-use std::fmt;
+use std::{
+    env, fmt, fs, io,
+    os::fd::{AsRawFd, FromRawFd, OwnedFd},
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::system::processes::{parse_proc_stat, ProcStat};
+
+// How often a process without a pidfd is looked up while waiting for it.
+const EXIT_POLL_MS: u64 = 100;
+
+// What the UI may send when no policy file says otherwise: the usual job
+// control and termination signals, nothing that fakes a fault or a timer.
+const DEFAULT_ALLOWED_SIGNALS: [&str; 9] = [
+    "SIGTERM", "SIGKILL", "SIGSTOP", "SIGCONT", "SIGHUP", "SIGINT", "SIGQUIT", "SIGUSR1", "SIGUSR2",
+];
 
 #[derive(Debug)]
 pub enum SignalError {
     PermissionDenied,
     InvalidPid,
+    NotFound,
+    ProcessChanged,
+    Unsupported,
+    Failed,
+    InvalidSignal,
 }
 
 impl fmt::Display for SignalError {
@@ -11,15 +35,449 @@ impl fmt::Display for SignalError {
         match self {
             Self::PermissionDenied => write!(f, "permission denied"),
             Self::InvalidPid => write!(f, "invalid pid"),
+            Self::NotFound => write!(f, "process not found"),
+            Self::ProcessChanged => write!(f, "process changed"),
+            Self::Unsupported => write!(f, "pidfd not supported"),
+            Self::Failed => write!(f, "signal failed"),
+            Self::InvalidSignal => write!(f, "invalid signal"),
         }
     }
 }
 
-pub fn validate_permission(current_uid: u32, target_uid: u32, pid: i32) -> Result<(), SignalError> {
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SignalAction {
+    Terminate,
+    CoreDump,
+    Ignore,
+    Stop,
+    Continue,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SignalInfo {
+    pub number: i32,
+    pub name: String,
+    pub description: String,
+    pub default_action: SignalAction,
+    pub allowed: bool,
+}
+
+// Linux numbering and default dispositions, as in signal(7).
+const STANDARD_SIGNALS: &[(i32, &str, SignalAction, &str)] = &[
+    (libc::SIGHUP, "HUP", SignalAction::Terminate, "Hangup; daemons usually reload their configuration"),
+    (libc::SIGINT, "INT", SignalAction::Terminate, "Interrupt from keyboard"),
+    (libc::SIGQUIT, "QUIT", SignalAction::CoreDump, "Quit from keyboard"),
+    (libc::SIGILL, "ILL", SignalAction::CoreDump, "Illegal instruction"),
+    (libc::SIGTRAP, "TRAP", SignalAction::CoreDump, "Trace or breakpoint trap"),
+    (libc::SIGABRT, "ABRT", SignalAction::CoreDump, "Abort"),
+    (libc::SIGBUS, "BUS", SignalAction::CoreDump, "Bus error"),
+    (libc::SIGFPE, "FPE", SignalAction::CoreDump, "Floating-point exception"),
+    (libc::SIGKILL, "KILL", SignalAction::Terminate, "Kill; cannot be caught or ignored"),
+    (libc::SIGUSR1, "USR1", SignalAction::Terminate, "User-defined signal 1"),
+    (libc::SIGSEGV, "SEGV", SignalAction::CoreDump, "Invalid memory reference"),
+    (libc::SIGUSR2, "USR2", SignalAction::Terminate, "User-defined signal 2"),
+    (libc::SIGPIPE, "PIPE", SignalAction::Terminate, "Write to a pipe with no readers"),
+    (libc::SIGALRM, "ALRM", SignalAction::Terminate, "Timer signal from alarm(2)"),
+    (libc::SIGTERM, "TERM", SignalAction::Terminate, "Termination request"),
+    (libc::SIGSTKFLT, "STKFLT", SignalAction::Terminate, "Stack fault on coprocessor (unused)"),
+    (libc::SIGCHLD, "CHLD", SignalAction::Ignore, "Child stopped or terminated"),
+    (libc::SIGCONT, "CONT", SignalAction::Continue, "Continue if stopped"),
+    (libc::SIGSTOP, "STOP", SignalAction::Stop, "Stop; cannot be caught or ignored"),
+    (libc::SIGTSTP, "TSTP", SignalAction::Stop, "Stop typed at terminal"),
+    (libc::SIGTTIN, "TTIN", SignalAction::Stop, "Terminal input for background process"),
+    (libc::SIGTTOU, "TTOU", SignalAction::Stop, "Terminal output for background process"),
+    (libc::SIGURG, "URG", SignalAction::Ignore, "Urgent condition on socket"),
+    (libc::SIGXCPU, "XCPU", SignalAction::CoreDump, "CPU time limit exceeded"),
+    (libc::SIGXFSZ, "XFSZ", SignalAction::CoreDump, "File size limit exceeded"),
+    (libc::SIGVTALRM, "VTALRM", SignalAction::Terminate, "Virtual alarm clock"),
+    (libc::SIGPROF, "PROF", SignalAction::Terminate, "Profiling timer expired"),
+    (libc::SIGWINCH, "WINCH", SignalAction::Ignore, "Window resize"),
+    (libc::SIGIO, "IO", SignalAction::Terminate, "I/O now possible"),
+    (libc::SIGPWR, "PWR", SignalAction::Terminate, "Power failure"),
+    (libc::SIGSYS, "SYS", SignalAction::CoreDump, "Bad system call"),
+];
+
+const SIGNAL_ALIASES: &[(&str, i32)] = &[("IOT", libc::SIGABRT), ("POLL", libc::SIGIO), ("CLD", libc::SIGCHLD)];
+
+// Named the way `kill -l` does: the lower half counts up from RTMIN, the
+// upper half down from RTMAX.
+fn realtime_name(signal: i32) -> Option<String> {
+    let (min, max) = (libc::SIGRTMIN(), libc::SIGRTMAX());
+    if !(min..=max).contains(&signal) {
+        return None;
+    }
+    let offset = signal - min;
+    Some(match offset {
+        0 => "SIGRTMIN".to_string(),
+        _ if signal == max => "SIGRTMAX".to_string(),
+        _ if offset <= (max - min) / 2 => format!("SIGRTMIN+{offset}"),
+        _ => format!("SIGRTMAX-{}", max - signal),
+    })
+}
+
+fn parse_realtime(name: &str) -> Option<i32> {
+    let offset = |rest: &str, sign: char| {
+        if rest.is_empty() {
+            return Some(0);
+        }
+        rest.strip_prefix(sign)?.parse::<i32>().ok().filter(|offset| *offset >= 0)
+    };
+    let (min, max) = (libc::SIGRTMIN(), libc::SIGRTMAX());
+    let signal = if let Some(rest) = name.strip_prefix("RTMIN") {
+        min.checked_add(offset(rest, '+')?)?
+    } else {
+        max.checked_sub(offset(name.strip_prefix("RTMAX")?, '-')?)?
+    };
+    (min..=max).contains(&signal).then_some(signal)
+}
+
+pub fn signal_name(signal: i32) -> Option<String> {
+    STANDARD_SIGNALS
+        .iter()
+        .find(|(number, ..)| *number == signal)
+        .map(|(_, name, ..)| format!("SIG{name}"))
+        .or_else(|| realtime_name(signal))
+}
+
+// Takes a number or a name with or without the SIG prefix, in any case, so
+// "15", "TERM", "sigterm" and "Sigterm" are the same signal. Numbers the C
+// library keeps for itself (32 and 33 with glibc) are rejected.
+pub fn parse_signal(spec: &str) -> Option<i32> {
+    let spec = spec.trim();
+    if let Ok(number) = spec.parse::<i32>() {
+        return signal_name(number).map(|_| number);
+    }
+    let upper = spec.to_ascii_uppercase();
+    let name = upper.strip_prefix("SIG").unwrap_or(&upper);
+    STANDARD_SIGNALS
+        .iter()
+        .find(|(_, standard, ..)| *standard == name)
+        .map(|(number, ..)| *number)
+        .or_else(|| SIGNAL_ALIASES.iter().find(|(alias, _)| *alias == name).map(|(_, number)| *number))
+        .or_else(|| parse_realtime(name))
+}
+
+pub fn list_signals(policy: &SignalPolicy) -> Vec<SignalInfo> {
+    let standard = STANDARD_SIGNALS.iter().map(|&(number, name, default_action, description)| SignalInfo {
+        number,
+        name: format!("SIG{name}"),
+        description: description.to_string(),
+        default_action,
+        allowed: policy.permits(number),
+    });
+    let realtime = (libc::SIGRTMIN()..=libc::SIGRTMAX()).filter_map(|number| {
+        Some(SignalInfo {
+            number,
+            name: realtime_name(number)?,
+            description: format!("Real-time signal {}; meaning is up to the application", number - libc::SIGRTMIN()),
+            default_action: SignalAction::Terminate,
+            allowed: policy.permits(number),
+        })
+    });
+    standard.chain(realtime).collect()
+}
+
+// Signals are named as in `parse_signal`. An empty allow list admits every
+// signal that is not denied; deny always wins.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignalPolicy {
+    pub allow: Vec<String>,
+    pub deny: Vec<String>,
+}
+
+impl Default for SignalPolicy {
+    fn default() -> Self {
+        Self {
+            allow: DEFAULT_ALLOWED_SIGNALS.iter().map(|name| name.to_string()).collect(),
+            deny: Vec::new(),
+        }
+    }
+}
+
+// Where the active policy came from. `path` is the file read at startup,
+// whether or not it exists; `error` says why the default was used instead.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct PolicySource {
+    pub path: Option<String>,
+    pub from_file: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SignalPolicyStatus {
+    pub policy: SignalPolicy,
+    pub source: PolicySource,
+}
+
+pub fn default_policy_path() -> Option<PathBuf> {
+    let config_home = env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+        .or_else(|| Some(PathBuf::from(env::var_os("HOME")?).join(".config")))?;
+    Some(config_home.join("com.lsm.desktop").join("signal-policy"))
+}
+
+impl SignalPolicy {
+    // One `allow = ...` or `deny = ...` line per list, names separated by
+    // spaces or commas; `#` starts a comment. A list the file leaves out keeps
+    // its default, and `allow =` with no names admits every signal. Errors
+    // name the offending line.
+    pub fn parse(raw: &str) -> Result<Self, String> {
+        let mut policy = Self::default();
+        for (index, line) in raw.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let invalid = |reason: String| format!("line {}: {reason}", index + 1);
+            let (key, names) = line
+                .split_once('=')
+                .ok_or_else(|| invalid("expected `allow = ...` or `deny = ...`".into()))?;
+            let names: Vec<String> = names
+                .split([' ', '\t', ','])
+                .filter(|name| !name.is_empty())
+                .map(str::to_string)
+                .collect();
+            if let Some(unknown) = names.iter().find(|name| parse_signal(name).is_none()) {
+                return Err(invalid(format!("unknown signal {unknown}")));
+            }
+            match key.trim() {
+                "allow" => policy.allow = names,
+                "deny" => policy.deny = names,
+                other => return Err(invalid(format!("unknown key {other}"))),
+            }
+        }
+        policy.normalized().map_err(|_| "invalid signal name".to_string())
+    }
+
+    // A missing or broken file falls back to the default, so a typo never
+    // widens what the UI may send. A broken file is also logged, since the
+    // UI only sees it through `get_signal_policy`.
+    pub fn load(path: &Path) -> (Self, PolicySource) {
+        let mut source = PolicySource {
+            path: Some(path.to_string_lossy().to_string()),
+            ..PolicySource::default()
+        };
+        let policy = match fs::read_to_string(path) {
+            Ok(raw) => match Self::parse(&raw) {
+                Ok(policy) => {
+                    source.from_file = true;
+                    policy
+                }
+                Err(error) => {
+                    source.error = Some(error);
+                    Self::default()
+                }
+            },
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Self::default(),
+            Err(error) => {
+                source.error = Some(error.to_string());
+                Self::default()
+            }
+        };
+        if let Some(error) = &source.error {
+            eprintln!("signal policy: ignoring {}: {error}", path.display());
+        }
+        (policy, source)
+    }
+
+    // Rewrites every entry to its canonical name, failing on the first one
+    // that does not parse.
+    pub fn normalized(&self) -> Result<Self, SignalError> {
+        let canonical = |names: &[String]| {
+            names
+                .iter()
+                .map(|name| parse_signal(name).and_then(signal_name).ok_or(SignalError::InvalidSignal))
+                .collect::<Result<Vec<_>, _>>()
+        };
+        Ok(Self {
+            allow: canonical(&self.allow)?,
+            deny: canonical(&self.deny)?,
+        })
+    }
+
+    pub fn permits(&self, signal: i32) -> bool {
+        let listed = |names: &[String]| names.iter().any(|name| parse_signal(name) == Some(signal));
+        !listed(&self.deny) && (self.allow.is_empty() || listed(&self.allow))
+    }
+}
+
+fn errno_error(err: io::Error) -> SignalError {
+    match err.raw_os_error() {
+        Some(libc::ESRCH) => SignalError::NotFound,
+        Some(libc::EPERM) => SignalError::PermissionDenied,
+        Some(libc::ENOSYS) => SignalError::Unsupported,
+        _ => SignalError::Failed,
+    }
+}
+
+// A handle on one specific process: unlike a PID it cannot be recycled, so
+// anything checked after opening it applies to the process that gets signalled.
+pub struct PidFd(OwnedFd);
+
+impl PidFd {
+    pub fn open(pid: i32) -> Result<Self, SignalError> {
+        if pid <= 1 {
+            return Err(SignalError::InvalidPid);
+        }
+        let fd = unsafe { libc::syscall(libc::SYS_pidfd_open, pid, 0) };
+        if fd < 0 {
+            return Err(errno_error(io::Error::last_os_error()));
+        }
+        Ok(Self(unsafe { OwnedFd::from_raw_fd(fd as i32) }))
+    }
+
+    pub fn send_signal(&self, signal: i32) -> Result<(), SignalError> {
+        let rc = unsafe {
+            libc::syscall(
+                libc::SYS_pidfd_send_signal,
+                self.0.as_raw_fd(),
+                signal,
+                std::ptr::null::<libc::siginfo_t>(),
+                0,
+            )
+        };
+        if rc < 0 {
+            return Err(errno_error(io::Error::last_os_error()));
+        }
+        Ok(())
+    }
+
+    // A pidfd polls readable once its process has exited, reaped or not.
+    pub fn wait_exit(&self, timeout: Duration) -> bool {
+        let mut fd = libc::pollfd {
+            fd: self.0.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        // Rounded up so a sub-millisecond wait does not become a busy poll.
+        let timeout_ms = timeout.as_micros().div_ceil(1_000).min(i32::MAX as u128) as i32;
+        unsafe { libc::poll(&mut fd, 1, timeout_ms) > 0 }
+    }
+}
+
+// Where a signal goes once the checks have passed: a pidfd where the kernel
+// has them, otherwise the PID with the identity to recheck before each kill(2).
+pub enum SignalTarget {
+    PidFd(PidFd),
+    Pid {
+        pid: i32,
+        start_time: u64,
+        proc_root: PathBuf,
+    },
+}
+
+impl SignalTarget {
+    pub fn send(&self, signal: i32) -> Result<(), SignalError> {
+        match self {
+            Self::PidFd(pidfd) => pidfd.send_signal(signal),
+            Self::Pid { pid, start_time, proc_root } => {
+                verify_identity(proc_root, *pid, *start_time)?;
+                kill(*pid, signal)
+            }
+        }
+    }
+
+    // Waits up to `timeout` and reports whether the process has exited.
+    pub fn wait_exit(&self, timeout: Duration) -> bool {
+        let (pid, start_time, proc_root) = match self {
+            Self::PidFd(pidfd) => return pidfd.wait_exit(timeout),
+            Self::Pid { pid, start_time, proc_root } => (*pid, *start_time, proc_root),
+        };
+        let deadline = Instant::now() + timeout;
+        loop {
+            // A zombie has exited even though its stat file is still there.
+            if matches!(process_state(proc_root, pid, start_time), None | Some('Z' | 'X')) {
+                return true;
+            }
+            let left = deadline.saturating_duration_since(Instant::now());
+            if left.is_zero() {
+                return false;
+            }
+            thread::sleep(left.min(Duration::from_millis(EXIT_POLL_MS)));
+        }
+    }
+}
+
+pub fn kill(pid: i32, signal: i32) -> Result<(), SignalError> {
+    if pid <= 1 {
+        return Err(SignalError::InvalidPid);
+    }
+    if unsafe { libc::kill(pid, signal) } != 0 {
+        return Err(errno_error(io::Error::last_os_error()));
+    }
+    Ok(())
+}
+
+// A PID plus its start time (field 22 of /proc/<pid>/stat) names one process;
+// a recycled PID comes back with a later start time.
+pub fn verify_identity(proc_root: &Path, pid: i32, start_time: u64) -> Result<(), SignalError> {
+    read_identified_stat(proc_root, pid, start_time).map(|_| ())
+}
+
+fn read_identified_stat(proc_root: &Path, pid: i32, start_time: u64) -> Result<ProcStat, SignalError> {
+    if pid <= 1 {
+        return Err(SignalError::InvalidPid);
+    }
+    let stat = fs::read_to_string(proc_root.join(pid.to_string()).join("stat")).map_err(|_| SignalError::NotFound)?;
+    let stat = parse_proc_stat(&stat).ok_or(SignalError::NotFound)?;
+    if stat.start_time != start_time {
+        return Err(SignalError::ProcessChanged);
+    }
+    Ok(stat)
+}
+
+// The state letter from proc(5) (R, S, D, Z, ...), or None once the process
+// is gone or its PID belongs to someone else.
+pub fn process_state(proc_root: &Path, pid: i32, start_time: u64) -> Option<char> {
+    read_identified_stat(proc_root, pid, start_time).ok().map(|stat| stat.state)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProcessUids {
+    pub real: u32,
+    pub effective: u32,
+    pub saved: u32,
+}
+
+// `Uid:` in /proc/<pid>/status lists real, effective, saved and filesystem UIDs.
+pub fn parse_status_uids(status: &str) -> Option<ProcessUids> {
+    let line = status.lines().find_map(|line| line.strip_prefix("Uid:"))?;
+    let mut uids = line.split_whitespace().map(|raw| raw.parse::<u32>().ok());
+    Some(ProcessUids {
+        real: uids.next()??,
+        effective: uids.next()??,
+        saved: uids.next()??,
+    })
+}
+
+pub fn read_process_uids(proc_root: &Path, pid: i32) -> Result<ProcessUids, SignalError> {
+    // 0 and negative PIDs address process groups in kill(2); never look them up.
     if pid <= 1 {
         return Err(SignalError::InvalidPid);
     }
-    if current_uid == 0 || current_uid == target_uid {
+    let status = fs::read_to_string(proc_root.join(pid.to_string()).join("status")).map_err(|err| {
+        match err.kind() {
+            // ESRCH shows up when the process exits while the file is being read.
+            io::ErrorKind::NotFound => SignalError::NotFound,
+            _ if err.raw_os_error() == Some(libc::ESRCH) => SignalError::NotFound,
+            _ => SignalError::PermissionDenied,
+        }
+    })?;
+    // A zombie that has been reaped mid-read leaves an empty or truncated file.
+    parse_status_uids(&status).ok_or(SignalError::NotFound)
+}
+
+// Mirrors kill(2): the caller's UID must match the target's real or saved
+// UID. The target's effective UID does not grant access, so a setuid-root
+// program started by the user can still be signalled, while a root daemon
+// that temporarily switched its effective UID to the user's cannot.
+pub fn validate_permission(current_uid: u32, target: &ProcessUids, pid: i32) -> Result<(), SignalError> {
+    if pid <= 1 {
+        return Err(SignalError::InvalidPid);
+    }
+    if current_uid == 0 || current_uid == target.real || current_uid == target.saved {
         return Ok(());
     }
     Err(SignalError::PermissionDenied)
@@ -27,20 +485,187 @@ pub fn validate_permission(current_uid: u32, target_uid: u32, pid: i32) -> Resul
 
 #[cfg(test)]
 mod tests {
+    use super::ProcessUids;
+    use crate::test_support::TempDir;
+
+    fn uids(real: u32, effective: u32, saved: u32) -> ProcessUids {
+        ProcessUids { real, effective, saved }
+    }
 
     #[test]
     fn deny_signal_for_different_user_when_not_root() {
-        let err = super::validate_permission(1000, 1001, 4242).unwrap_err();
+        let err = super::validate_permission(1000, &uids(1001, 1001, 1001), 4242).unwrap_err();
         assert_eq!(err.to_string(), "permission denied");
     }
 
     #[test]
     fn allow_signal_for_same_user() {
-        assert!(super::validate_permission(1000, 1000, 4242).is_ok());
+        assert!(super::validate_permission(1000, &uids(1000, 1000, 1000), 4242).is_ok());
     }
 
     #[test]
     fn reject_pid_one() {
-        assert!(super::validate_permission(0, 0, 1).is_err());
+        assert!(super::validate_permission(0, &uids(0, 0, 0), 1).is_err());
+    }
+
+    #[test]
+    fn match_real_or_saved_uid_but_not_effective() {
+        // setuid-root binary launched by the user.
+        assert!(super::validate_permission(1000, &uids(1000, 0, 0), 4242).is_ok());
+        // Root daemon with its effective UID dropped to the user's.
+        assert!(super::validate_permission(1000, &uids(0, 1000, 0), 4242).is_err());
+    }
+
+    #[test]
+    fn verify_identity_compares_start_times() {
+        let root = TempDir::new("identity");
+        std::fs::create_dir_all(root.join("4242")).unwrap();
+        std::fs::write(
+            root.join("4242/stat"),
+            "4242 (sleep) S 1 4242 4242 0 -1 4194304 90 0 0 0 0 0 0 0 20 0 1 0 777 1000 100 0\n",
+        )
+        .unwrap();
+
+        let same = super::verify_identity(&root, 4242, 777);
+        let recycled = super::verify_identity(&root, 4242, 555);
+        let gone = super::verify_identity(&root, 4343, 777);
+
+        assert!(same.is_ok());
+        assert!(matches!(recycled, Err(super::SignalError::ProcessChanged)));
+        assert!(matches!(gone, Err(super::SignalError::NotFound)));
+    }
+
+    #[test]
+    fn parse_status_uids_reads_real_effective_and_saved() {
+        let status = "Name:\tpasswd\nState:\tS (sleeping)\nUid:\t1000\t0\t0\t0\nGid:\t1000\t1000\t1000\t1000\n";
+        assert_eq!(super::parse_status_uids(status), Some(uids(1000, 0, 0)));
+        assert_eq!(super::parse_status_uids("Name:\tzombie\n"), None);
+    }
+
+    #[test]
+    fn parse_signal_accepts_names_numbers_and_aliases() {
+        for spec in ["15", "TERM", "sigterm", "Sigterm", " SIGTERM "] {
+            assert_eq!(super::parse_signal(spec), Some(libc::SIGTERM), "{spec}");
+        }
+        assert_eq!(super::parse_signal("hup"), Some(libc::SIGHUP));
+        assert_eq!(super::parse_signal("SIGIOT"), Some(libc::SIGABRT));
+        assert_eq!(super::signal_name(libc::SIGUSR2).as_deref(), Some("SIGUSR2"));
+        for bad in ["0", "-9", "32", "SIGFOO", "", "RTMIN-1", "RTMAX+1"] {
+            assert_eq!(super::parse_signal(bad), None, "{bad}");
+        }
+    }
+
+    #[test]
+    fn realtime_signals_round_trip_through_their_names() {
+        let (min, max) = (libc::SIGRTMIN(), libc::SIGRTMAX());
+        assert_eq!(super::parse_signal("SIGRTMIN+3"), Some(min + 3));
+        assert_eq!(super::parse_signal("rtmax-1"), Some(max - 1));
+        assert_eq!(super::signal_name(min).as_deref(), Some("SIGRTMIN"));
+        assert_eq!(super::signal_name(max).as_deref(), Some("SIGRTMAX"));
+        for number in min..=max {
+            let name = super::signal_name(number).unwrap();
+            assert_eq!(super::parse_signal(&name), Some(number), "{name}");
+        }
+        assert_eq!(super::parse_signal(&format!("RTMIN+{}", max - min + 1)), None);
+    }
+
+    #[test]
+    fn signal_policy_denies_before_it_allows() {
+        let names = |names: &[&str]| names.iter().map(|name| name.to_string()).collect::<Vec<_>>();
+        let conservative = super::SignalPolicy::default();
+        assert!(conservative.permits(libc::SIGUSR1));
+        assert!(!conservative.permits(libc::SIGSEGV));
+        assert!(!conservative.permits(libc::SIGRTMIN()));
+
+        let policy = super::SignalPolicy {
+            allow: names(&["term", "9", "SIGHUP"]),
+            deny: names(&["KILL"]),
+        };
+        assert!(policy.permits(libc::SIGTERM));
+        assert!(policy.permits(libc::SIGHUP));
+        assert!(!policy.permits(libc::SIGKILL));
+        assert!(!policy.permits(libc::SIGUSR1));
+        assert_eq!(policy.normalized().unwrap().allow, names(&["SIGTERM", "SIGKILL", "SIGHUP"]));
+        assert!(super::SignalPolicy { allow: names(&["SIGNOPE"]), deny: Vec::new() }.normalized().is_err());
+
+        let listed = super::list_signals(&policy);
+        let usr1 = listed.iter().find(|info| info.name == "SIGUSR1").unwrap();
+        assert!(!usr1.allowed);
+        assert!(listed.iter().any(|info| info.name == "SIGRTMIN+1"));
+    }
+
+    #[test]
+    fn signal_policy_loads_from_a_file_and_falls_back_to_the_default() {
+        let dir = TempDir::new("signal-policy");
+        let path = dir.join("signal-policy");
+        std::fs::write(&path, "# local overrides\nallow = term, kill  usr1\n").unwrap();
+        let (loaded, source) = super::SignalPolicy::load(&path);
+        assert_eq!(loaded.allow, ["SIGTERM", "SIGKILL", "SIGUSR1"]);
+        assert!(loaded.deny.is_empty());
+        assert!(source.from_file && source.error.is_none());
+        assert_eq!(source.path.as_deref(), path.to_str());
+
+        let open = super::SignalPolicy::parse("allow =\ndeny = SIGKILL\n").unwrap();
+        assert!(open.permits(libc::SIGSEGV));
+        assert!(!open.permits(libc::SIGKILL));
+
+        std::fs::write(&path, "deny = SIGKILL\nallow = SIGTERM SIGNOPE\n").unwrap();
+        let (broken, source) = super::SignalPolicy::load(&path);
+        assert_eq!(broken, super::SignalPolicy::default());
+        assert!(!source.from_file);
+        assert_eq!(source.error.as_deref(), Some("line 2: unknown signal SIGNOPE"));
+
+        let (missing, source) = super::SignalPolicy::load(&dir.join("missing"));
+        assert_eq!(missing, super::SignalPolicy::default());
+        assert!(!source.from_file && source.error.is_none());
+        assert_eq!(
+            super::SignalPolicy::parse("permit = SIGTERM\n"),
+            Err("line 1: unknown key permit".to_string())
+        );
+    }
+
+    #[test]
+    fn pidfd_signals_the_opened_process() {
+        let mut child = std::process::Command::new("sleep").arg("30").spawn().unwrap();
+        let pidfd = match super::PidFd::open(child.id() as i32) {
+            Ok(pidfd) => pidfd,
+            // Pre-5.3 kernels and some sandboxes have no pidfd_open.
+            Err(super::SignalError::Unsupported) => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return;
+            }
+            Err(err) => panic!("pidfd_open failed: {err}"),
+        };
+        pidfd.send_signal(libc::SIGKILL).unwrap();
+        let status = child.wait().unwrap();
+        assert_eq!(std::os::unix::process::ExitStatusExt::signal(&status), Some(libc::SIGKILL));
+        // Once reaped, the handle still refers to the dead process, never a new one.
+        assert!(matches!(pidfd.send_signal(libc::SIGTERM), Err(super::SignalError::NotFound)));
+    }
+
+    #[test]
+    fn pid_target_rechecks_identity_and_sees_zombies_as_exited() {
+        let mut child = std::process::Command::new("sleep").arg("30").spawn().unwrap();
+        let pid = child.id() as i32;
+        let root = std::path::PathBuf::from("/proc");
+        let stat = std::fs::read_to_string(root.join(pid.to_string()).join("stat")).unwrap();
+        let start_time = crate::system::processes::parse_proc_stat(&stat).unwrap().start_time;
+        let target = |start_time| super::SignalTarget::Pid {
+            pid,
+            start_time,
+            proc_root: root.clone(),
+        };
+
+        let stale = target(start_time + 1).send(libc::SIGTERM);
+        let alive = target(start_time).wait_exit(std::time::Duration::from_millis(50));
+        target(start_time).send(libc::SIGTERM).unwrap();
+        // Not reaped yet, so /proc still lists it as a zombie.
+        let exited = target(start_time).wait_exit(std::time::Duration::from_secs(2));
+        let _ = child.wait();
+
+        assert!(matches!(stale, Err(super::SignalError::ProcessChanged)));
+        assert!(!alive);
+        assert!(exited);
     }
 }
@@ -0,0 +1,444 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    env,
+    fs::{self, File, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::system::{
+    history::ONE_MINUTE_MS,
+    models::{HistorySeries, RollupPoint},
+};
+
+const DAY_MS: i64 = 86_400_000;
+const SEGMENT_EXT: &str = "seg";
+const MAINTENANCE_EVERY_MS: i64 = 3_600_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StoreConfig {
+    pub max_age_days: u64,
+    pub max_bytes: u64,
+    pub compact_after_days: u64,
+}
+
+impl Default for StoreConfig {
+    fn default() -> Self {
+        Self {
+            max_age_days: 30,
+            max_bytes: 256 * 1024 * 1024,
+            compact_after_days: 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StoredPoint {
+    pub series: HistorySeries,
+    pub width_ms: i64,
+    pub point: RollupPoint,
+}
+
+pub fn default_store_dir() -> Option<PathBuf> {
+    let data_home = env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+        .or_else(|| Some(PathBuf::from(env::var_os("HOME")?).join(".local/share")))?;
+    Some(data_home.join("com.lsm.desktop").join("history"))
+}
+
+fn checksum(payload: &str) -> u32 {
+    // FNV-1a; enough to reject torn or bit-flipped lines.
+    payload
+        .bytes()
+        .fold(0x811c_9dc5_u32, |hash, byte| (hash ^ byte as u32).wrapping_mul(0x0100_0193))
+}
+
+fn encode(record: &StoredPoint) -> String {
+    let point = &record.point;
+    let payload = format!(
+        "{}\t{}\t{}\t{}\t{}\t{}",
+        point.ts_ms,
+        record.series.as_str(),
+        record.width_ms,
+        point.min,
+        point.avg,
+        point.max
+    );
+    format!("{payload}\t{:08x}\n", checksum(&payload))
+}
+
+fn decode(line: &str) -> Option<StoredPoint> {
+    let (payload, sum) = line.rsplit_once('\t')?;
+    if u32::from_str_radix(sum, 16).ok()? != checksum(payload) {
+        return None;
+    }
+    let mut fields = payload.split('\t');
+    let ts_ms = fields.next()?.parse().ok()?;
+    let series = HistorySeries::from_name(fields.next()?)?;
+    let width_ms = fields.next()?.parse().ok()?;
+    let min = fields.next()?.parse().ok()?;
+    let avg = fields.next()?.parse().ok()?;
+    let max = fields.next()?.parse().ok()?;
+    Some(StoredPoint {
+        series,
+        width_ms,
+        point: RollupPoint { ts_ms, min, avg, max },
+    })
+}
+
+// Averages are weighted by each record's width, so a late 10s rollup folded
+// into an already compacted minute doesn't count as much as the whole minute.
+fn rebucket(records: impl Iterator<Item = StoredPoint>, width_ms: i64) -> Vec<RollupPoint> {
+    let mut buckets: BTreeMap<i64, (RollupPoint, f64)> = BTreeMap::new();
+    for StoredPoint { point, width_ms: weight, .. } in records {
+        let start_ms = point.ts_ms - point.ts_ms.rem_euclid(width_ms);
+        let weight = weight.max(1) as f64;
+        buckets
+            .entry(start_ms)
+            .and_modify(|(bucket, total)| {
+                bucket.min = bucket.min.min(point.min);
+                bucket.max = bucket.max.max(point.max);
+                bucket.avg += point.avg * weight;
+                *total += weight;
+            })
+            .or_insert((
+                RollupPoint {
+                    ts_ms: start_ms,
+                    avg: point.avg * weight,
+                    ..point
+                },
+                weight,
+            ));
+    }
+    buckets
+        .into_values()
+        .map(|(bucket, total)| RollupPoint {
+            avg: bucket.avg / total,
+            ..bucket
+        })
+        .collect()
+}
+
+// Stored points fill in whatever the in-memory tiers no longer hold.
+pub fn merge_history(stored: Vec<RollupPoint>, memory: Vec<RollupPoint>) -> Vec<RollupPoint> {
+    let memory_start = memory.first().map_or(i64::MAX, |point| point.ts_ms);
+    stored
+        .into_iter()
+        .filter(|point| point.ts_ms < memory_start)
+        .chain(memory)
+        .collect()
+}
+
+fn sync_dir(dir: &Path) -> io::Result<()> {
+    File::open(dir)?.sync_all()
+}
+
+// A crash mid-append can leave a partial last line; cut it off so the next
+// append starts on a clean line.
+fn repair_tail(path: &Path) -> io::Result<()> {
+    let bytes = fs::read(path)?;
+    if bytes.last().is_none_or(|byte| *byte == b'\n') {
+        return Ok(());
+    }
+    let keep = bytes.iter().rposition(|byte| *byte == b'\n').map_or(0, |i| i + 1);
+    OpenOptions::new().write(true).open(path)?.set_len(keep as u64)
+}
+
+pub struct MetricStore {
+    dir: PathBuf,
+    config: StoreConfig,
+    compacted: BTreeSet<i64>,
+    last_maintenance_ms: Option<i64>,
+}
+
+impl MetricStore {
+    pub fn open(dir: &Path, config: StoreConfig) -> io::Result<Self> {
+        fs::create_dir_all(dir)?;
+        let store = Self {
+            dir: dir.to_path_buf(),
+            config,
+            compacted: BTreeSet::new(),
+            last_maintenance_ms: None,
+        };
+        for (_, path) in store.segments()? {
+            repair_tail(&path)?;
+        }
+        Ok(store)
+    }
+
+    fn segment_path(&self, day: i64) -> PathBuf {
+        self.dir.join(format!("{day}.{SEGMENT_EXT}"))
+    }
+
+    fn segments(&self) -> io::Result<Vec<(i64, PathBuf)>> {
+        let mut segments: Vec<(i64, PathBuf)> = fs::read_dir(&self.dir)?
+            .flatten()
+            .filter_map(|entry| {
+                let path = entry.path();
+                if path.extension()? != SEGMENT_EXT {
+                    return None;
+                }
+                let day = path.file_stem()?.to_str()?.parse().ok()?;
+                Some((day, path))
+            })
+            .collect();
+        segments.sort();
+        Ok(segments)
+    }
+
+    fn read_segment(path: &Path) -> Vec<StoredPoint> {
+        fs::read_to_string(path)
+            .map(|raw| raw.lines().filter_map(decode).collect())
+            .unwrap_or_default()
+    }
+
+    pub fn append(&mut self, records: &[StoredPoint], now_ms: i64) -> io::Result<()> {
+        let mut by_day: BTreeMap<i64, String> = BTreeMap::new();
+        for record in records {
+            by_day
+                .entry(record.point.ts_ms.div_euclid(DAY_MS))
+                .or_default()
+                .push_str(&encode(record));
+        }
+        for (day, lines) in by_day {
+            let path = self.segment_path(day);
+            let created = !path.exists();
+            let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+            file.write_all(lines.as_bytes())?;
+            file.sync_data()?;
+            // The new directory entry isn't durable until the directory is synced.
+            if created {
+                sync_dir(&self.dir)?;
+            }
+            // A bucket closing just after midnight lands in a day that may
+            // already be compacted; fold it in so the segment stays 1min wide.
+            if self.compacted.contains(&day) {
+                self.compact(&path)?;
+            }
+        }
+
+        let due = self
+            .last_maintenance_ms
+            .is_none_or(|last| now_ms - last >= MAINTENANCE_EVERY_MS);
+        if due {
+            self.maintain(now_ms)?;
+        }
+        Ok(())
+    }
+
+    // Days with a segment overlapping the range, oldest first. Together with
+    // `query_day` this lets callers walk a long range one day at a time.
+    pub fn days(&self, start_ms: i64, end_ms: i64) -> Vec<i64> {
+        let Ok(segments) = self.segments() else {
+            return Vec::new();
+        };
+        segments
+            .into_iter()
+            .map(|(day, _)| day)
+            .filter(|day| {
+                let day_start = day.saturating_mul(DAY_MS);
+                day_start <= end_ms && day_start.saturating_add(DAY_MS) > start_ms
+            })
+            .collect()
+    }
+
+    // Buckets never straddle midnight, so each day can be rebucketed on its own.
+    pub fn query_day(
+        &self,
+        day: i64,
+        series: HistorySeries,
+        start_ms: i64,
+        end_ms: i64,
+        width_ms: i64,
+    ) -> Vec<RollupPoint> {
+        let mut points: Vec<StoredPoint> = Self::read_segment(&self.segment_path(day))
+            .into_iter()
+            .filter(|record| record.series == series)
+            .filter(|record| record.point.ts_ms >= start_ms && record.point.ts_ms <= end_ms)
+            .collect();
+        points.sort_by_key(|record| record.point.ts_ms);
+        if points.iter().all(|record| record.width_ms >= width_ms) {
+            return points.into_iter().map(|record| record.point).collect();
+        }
+        rebucket(points.into_iter(), width_ms)
+    }
+
+    pub fn query(&self, series: HistorySeries, start_ms: i64, end_ms: i64, width_ms: i64) -> Vec<RollupPoint> {
+        self.days(start_ms, end_ms)
+            .into_iter()
+            .flat_map(|day| self.query_day(day, series, start_ms, end_ms, width_ms))
+            .collect()
+    }
+
+    pub fn maintain(&mut self, now_ms: i64) -> io::Result<()> {
+        self.last_maintenance_ms = Some(now_ms);
+        let today = now_ms.div_euclid(DAY_MS);
+
+        let mut segments = Vec::new();
+        for (day, path) in self.segments()? {
+            if today - day > self.config.max_age_days as i64 {
+                fs::remove_file(&path)?;
+                self.compacted.remove(&day);
+                continue;
+            }
+            if today - day >= self.config.compact_after_days as i64 && !self.compacted.contains(&day) {
+                self.compact(&path)?;
+                self.compacted.insert(day);
+            }
+            segments.push((day, path));
+        }
+
+        // Oldest days go first once the store outgrows its budget; today's
+        // segment is kept so live appends always have somewhere to land.
+        let mut total: u64 = segments
+            .iter()
+            .filter_map(|(_, path)| fs::metadata(path).ok())
+            .map(|meta| meta.len())
+            .sum();
+        for (day, path) in segments {
+            if total <= self.config.max_bytes || day >= today {
+                break;
+            }
+            total = total.saturating_sub(fs::metadata(&path).map_or(0, |meta| meta.len()));
+            fs::remove_file(&path)?;
+            self.compacted.remove(&day);
+        }
+        Ok(())
+    }
+
+    // Rewrites a day of 10s rollups as 1min rollups. The new file is fully
+    // written and synced before it replaces the old one, so a crash leaves
+    // either the old or the new segment, never a mix.
+    fn compact(&self, path: &Path) -> io::Result<()> {
+        let records = Self::read_segment(path);
+        if records.iter().all(|record| record.width_ms >= ONE_MINUTE_MS) {
+            return Ok(());
+        }
+
+        let mut by_series: BTreeMap<HistorySeries, Vec<StoredPoint>> = BTreeMap::new();
+        for record in records {
+            by_series.entry(record.series).or_default().push(record);
+        }
+        let mut lines = String::new();
+        for (series, points) in by_series {
+            for point in rebucket(points.into_iter(), ONE_MINUTE_MS) {
+                lines.push_str(&encode(&StoredPoint {
+                    series,
+                    width_ms: ONE_MINUTE_MS,
+                    point,
+                }));
+            }
+        }
+
+        let tmp = path.with_extension(format!("{SEGMENT_EXT}.tmp"));
+        let mut file = File::create(&tmp)?;
+        file.write_all(lines.as_bytes())?;
+        file.sync_all()?;
+        fs::rename(&tmp, path)?;
+        sync_dir(&self.dir)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::{self, OpenOptions};
+    use std::io::Write;
+
+    use super::{MetricStore, StoreConfig, StoredPoint, DAY_MS};
+    use crate::{
+        system::models::{HistorySeries, RollupPoint},
+        test_support::TempDir,
+    };
+
+    fn record(ts_ms: i64, value: f64) -> StoredPoint {
+        StoredPoint {
+            series: HistorySeries::Cpu,
+            width_ms: 10_000,
+            point: RollupPoint {
+                ts_ms,
+                min: value - 1.0,
+                avg: value,
+                max: value + 1.0,
+            },
+        }
+    }
+
+    #[test]
+    fn store_survives_reopen_and_skips_torn_lines() {
+        let dir = TempDir::new("store-reopen");
+        let now = 20_000 * DAY_MS;
+        {
+            let mut store = MetricStore::open(&dir, StoreConfig::default()).unwrap();
+            store.append(&[record(now, 10.0), record(now + 10_000, 20.0)], now).unwrap();
+        }
+        // Simulate a crash halfway through writing a line.
+        let segment = dir.join("20000.seg");
+        OpenOptions::new().append(true).open(&segment).unwrap().write_all(b"1728").unwrap();
+
+        let mut store = MetricStore::open(&dir, StoreConfig::default()).unwrap();
+        store.append(&[record(now + 20_000, 30.0)], now).unwrap();
+        let points = store.query(HistorySeries::Cpu, now, now + DAY_MS, 10_000);
+
+        let avgs: Vec<f64> = points.iter().map(|point| point.avg).collect();
+        assert_eq!(avgs, vec![10.0, 20.0, 30.0]);
+    }
+
+    #[test]
+    fn maintenance_compacts_old_days_and_drops_expired_ones() {
+        let dir = TempDir::new("store-maintain");
+        let today = 20_010 * DAY_MS;
+        let yesterday = today - DAY_MS;
+        let expired = today - 40 * DAY_MS;
+        let mut store = MetricStore::open(&dir, StoreConfig::default()).unwrap();
+        let old: Vec<StoredPoint> = (0..12).map(|i| record(yesterday + i * 10_000, i as f64)).collect();
+        store.append(&old, yesterday).unwrap();
+        store.append(&[record(expired, 1.0)], yesterday).unwrap();
+
+        store.maintain(today).unwrap();
+        let compacted = store.query(HistorySeries::Cpu, yesterday, today, 10_000);
+        let expired_points = store.query(HistorySeries::Cpu, expired, expired + DAY_MS, 10_000);
+
+        assert_eq!(compacted.len(), 2);
+        assert!((compacted[0].avg - 2.5).abs() < 1e-9);
+        assert_eq!((compacted[1].min, compacted[1].max), (5.0, 12.0));
+        assert!(expired_points.is_empty());
+    }
+
+    #[test]
+    fn late_appends_to_a_compacted_day_are_recompacted() {
+        let dir = TempDir::new("store-late");
+        let today = 20_020 * DAY_MS;
+        let last_minute = today - 60_000;
+        let mut store = MetricStore::open(&dir, StoreConfig::default()).unwrap();
+        let early: Vec<StoredPoint> = (0..5).map(|i| record(last_minute + i * 10_000, 1.0)).collect();
+        store.append(&early, today).unwrap();
+        store.maintain(today).unwrap();
+
+        // The 23:59:50 bucket only closes after midnight.
+        store.append(&[record(last_minute + 50_000, 7.0)], today).unwrap();
+        let contents = fs::read_to_string(dir.join("20019.seg")).unwrap();
+        let points = store.query(HistorySeries::Cpu, last_minute, today, 10_000);
+
+        assert_eq!(contents.lines().count(), 1);
+        assert_eq!(points.len(), 1);
+        // The compacted minute weighs six times as much as the late 10s rollup.
+        assert!((points[0].avg - 13.0 / 7.0).abs() < 1e-9);
+        assert_eq!((points[0].min, points[0].max), (0.0, 8.0));
+    }
+
+    #[test]
+    fn merge_history_prefers_memory_where_it_overlaps() {
+        let point = |ts_ms: i64, avg: f64| RollupPoint {
+            ts_ms,
+            min: avg,
+            avg,
+            max: avg,
+        };
+        let stored = vec![point(0, 1.0), point(10_000, 2.0), point(20_000, 9.0)];
+        let memory = vec![point(20_000, 3.0), point(30_000, 4.0)];
+        let merged = super::merge_history(stored, memory);
+        let avgs: Vec<f64> = merged.iter().map(|point| point.avg).collect();
+        assert_eq!(avgs, vec![1.0, 2.0, 3.0, 4.0]);
+    }
+}
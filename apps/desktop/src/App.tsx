@@ -1,4 +1,6 @@
 import { useEffect, useState } from "react";
+import type { UnlistenFn } from "@tauri-apps/api/event";
+import { tauriApi } from "@/lib/tauriApi";
 import { useSystemStore } from "@/stores/systemStore";
 import { PerformanceTab } from "@/features/taskManager/PerformanceTab";
 import { ProcessesTab } from "@/features/taskManager/ProcessesTab";
@@ -7,7 +9,8 @@ import { ServicesTab } from "@/features/taskManager/ServicesTab";
 type TabKey = "performance" | "processes" | "services";
 
 export default function App() {
-  const refreshMetrics = useSystemStore((s) => s.refreshMetrics);
+  const bootstrapMetrics = useSystemStore((s) => s.bootstrapMetrics);
+  const applySnapshot = useSystemStore((s) => s.applySnapshot);
   const refreshProcesses = useSystemStore((s) => s.refreshProcesses);
   const metrics = useSystemStore((s) => s.metrics);
   const processes = useSystemStore((s) => s.processes);
@@ -15,23 +18,35 @@ export default function App() {
 
   useEffect(() => {
     let mounted = true;
+    let unlisten: UnlistenFn | undefined;
     const boot = async () => {
-      await refreshMetrics();
+      const stop = await tauriApi.onMetricsSnapshot(applySnapshot);
+      if (!mounted) {
+        stop();
+        return;
+      }
+      unlisten = stop;
+      // Bootstrap first so the sampler's incremental events have windows to
+      // append to.
+      await bootstrapMetrics();
+      await tauriApi.startMetricsSampler(useSystemStore.getState().intervalMs);
       await refreshProcesses();
     };
     void boot();
 
+    // Metrics arrive as sampler events; only the process list is polled.
     const timer = setInterval(() => {
       if (!mounted) return;
-      void refreshMetrics();
       void refreshProcesses();
     }, 1000);
 
     return () => {
       mounted = false;
       clearInterval(timer);
+      unlisten?.();
+      void tauriApi.stopMetricsSampler();
     };
-  }, [refreshMetrics, refreshProcesses]);
+  }, [applySnapshot, bootstrapMetrics, refreshProcesses]);
 
   return (
     <main className="min-h-screen bg-[radial-gradient(circle_at_top,_#1f2937,_#05070f_55%)] p-3 text-slate-100 md:p-4">
@@ -4,21 +4,22 @@ import { Button } from "@/components/ui/button";
 
 type ProcessActionsProps = {
   pid: number;
+  startTime: number;
 };
 
-export function ProcessActions({ pid }: ProcessActionsProps) {
+export function ProcessActions({ pid, startTime }: ProcessActionsProps) {
   return (
     <div className="inline-flex gap-2">
-      <Button variant="outline" size="sm" onClick={() => void tauriApi.sendProcessSignal(pid, "Sigterm" as ProcessSignal)}>
+      <Button variant="outline" size="sm" onClick={() => void tauriApi.sendProcessSignal(pid, startTime, "SIGTERM" as ProcessSignal)}>
         TERM
       </Button>
-      <Button variant="destructive" size="sm" onClick={() => void tauriApi.sendProcessSignal(pid, "Sigkill" as ProcessSignal)}>
+      <Button variant="destructive" size="sm" onClick={() => void tauriApi.sendProcessSignal(pid, startTime, "SIGKILL" as ProcessSignal)}>
         KILL
       </Button>
-      <Button variant="secondary" size="sm" onClick={() => void tauriApi.sendProcessSignal(pid, "Sigstop" as ProcessSignal)}>
+      <Button variant="secondary" size="sm" onClick={() => void tauriApi.sendProcessSignal(pid, startTime, "SIGSTOP" as ProcessSignal)}>
         STOP
       </Button>
-      <Button variant="secondary" size="sm" onClick={() => void tauriApi.sendProcessSignal(pid, "Sigcont" as ProcessSignal)}>
+      <Button variant="secondary" size="sm" onClick={() => void tauriApi.sendProcessSignal(pid, startTime, "SIGCONT" as ProcessSignal)}>
         CONT
       </Button>
     </div>
@@ -29,7 +29,7 @@ export function ProcessTable({ rows }: { rows: ProcessRow[] }) {
             <TableCell>{row.cpu_percent.toFixed(1)}%</TableCell>
             <TableCell>{row.mem_percent.toFixed(1)}%</TableCell>
             <TableCell>
-              <ProcessActions pid={row.pid} />
+              <ProcessActions pid={row.pid} startTime={row.start_time} />
             </TableCell>
           </TableRow>
         ))}
@@ -52,7 +52,7 @@ export function ProcessesTab() {
                 <td className="px-3 py-2 text-right">{row.cpu_percent.toFixed(1)}%</td>
                 <td className="px-3 py-2 text-right">{row.mem_percent.toFixed(1)}%</td>
                 <td className="px-3 py-2">
-                  <ProcessActionButtons pid={row.pid} onDone={refreshProcesses} />
+                  <ProcessActionButtons pid={row.pid} startTime={row.start_time} onDone={refreshProcesses} />
                 </td>
               </tr>
             ))}
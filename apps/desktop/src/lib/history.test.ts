@@ -0,0 +1,46 @@
+import { describe, expect, it } from "vitest";
+import { useSystemStore } from "@/stores/systemStore";
+import type { MetricsSnapshot, NetInterfaceUsage } from "@/types/system";
+import { HISTORY_WINDOW_MS, mergeSnapshot } from "./history";
+
+const points = (...ts: number[]) => ts.map((ts_ms) => ({ ts_ms, value: ts_ms / 1000 }));
+
+const snapshot = (ts_ms: number, overrides: Partial<MetricsSnapshot>): MetricsSnapshot => ({
+  ...useSystemStore.getState().metrics,
+  ts_ms,
+  ...overrides,
+});
+
+describe("mergeSnapshot", () => {
+  it("appends new points and trims to the window", () => {
+    const prev = snapshot(2_000, { cpu_history: points(1_000, 2_000) });
+    const next = snapshot(HISTORY_WINDOW_MS + 1_500, { cpu_history: points(2_000, HISTORY_WINDOW_MS + 1_500) });
+    const merged = mergeSnapshot(prev, next);
+    expect(merged.cpu_history.map((p) => p.ts_ms)).toEqual([2_000, HISTORY_WINDOW_MS + 1_500]);
+    expect(merged.ts_ms).toBe(HISTORY_WINDOW_MS + 1_500);
+  });
+
+  it("matches devices by name and ignores stale responses", () => {
+    const iface = (name: string, ts: number[]): NetInterfaceUsage => ({
+      name,
+      rx_bps: 0,
+      tx_bps: 0,
+      rx_pps: 0,
+      tx_pps: 0,
+      rx_errors_ps: 0,
+      tx_errors_ps: 0,
+      rx_drops_ps: 0,
+      tx_drops_ps: 0,
+      rx_fifo_ps: 0,
+      tx_fifo_ps: 0,
+      rx_history: points(...ts),
+      tx_history: [],
+    });
+    const prev = snapshot(2_000, { net_interfaces: [iface("eth0", [1_000, 2_000]), iface("wg0", [2_000])] });
+    const next = snapshot(3_000, { net_interfaces: [iface("wg0", [3_000])] });
+    const merged = mergeSnapshot(prev, next);
+    expect(merged.net_interfaces.map((i) => i.name)).toEqual(["wg0"]);
+    expect(merged.net_interfaces[0].rx_history.map((p) => p.ts_ms)).toEqual([2_000, 3_000]);
+    expect(mergeSnapshot(merged, prev)).toBe(merged);
+  });
+});
@@ -0,0 +1,65 @@
+import type { HistoryGap, MetricPoint, MetricsSnapshot } from "@/types/system";
+
+// Fallback for the backend's live window until a snapshot reports it.
+export const HISTORY_WINDOW_MS = 60_000;
+
+type Fields = Record<string, unknown>;
+
+const isHistoryKey = (key: string) => key === "history" || key.endsWith("_history");
+
+// Cores, interfaces, disks, sensors and batteries are matched across polls by
+// these keys, so a device appearing or vanishing does not shift the others.
+const identity = (item: unknown) => {
+  const fields = item as Fields;
+  return fields.cpu ?? fields.id ?? fields.name;
+};
+
+function appendPoints(prev: MetricPoint[] | undefined, next: MetricPoint[], cutoffMs: number): MetricPoint[] {
+  const lastTs = prev?.length ? prev[prev.length - 1].ts_ms : -Infinity;
+  return [...(prev ?? []), ...next.filter((point) => point.ts_ms > lastTs)].filter(
+    (point) => point.ts_ms >= cutoffMs,
+  );
+}
+
+function appendGaps(prev: HistoryGap[], next: HistoryGap[], cutoffMs: number): HistoryGap[] {
+  const known = new Set(prev.map((gap) => gap.start_ms));
+  return [...prev, ...next.filter((gap) => !known.has(gap.start_ms))].filter((gap) => gap.end_ms >= cutoffMs);
+}
+
+function merge(prev: unknown, next: unknown, cutoffMs: number, key = ""): unknown {
+  if (isHistoryKey(key)) {
+    if (Array.isArray(next)) {
+      return appendPoints(Array.isArray(prev) ? prev : undefined, next, cutoffMs);
+    }
+    // Per-mode CPU histories are an object of series.
+    const before = (prev ?? {}) as Record<string, MetricPoint[]>;
+    return Object.fromEntries(
+      Object.entries(next as Record<string, MetricPoint[]>).map(([mode, points]) => [
+        mode,
+        appendPoints(before[mode], points, cutoffMs),
+      ]),
+    );
+  }
+  if (Array.isArray(next)) {
+    const previous = new Map((Array.isArray(prev) ? prev : []).map((item) => [identity(item), item]));
+    return next.map((item) => merge(previous.get(identity(item)), item, cutoffMs));
+  }
+  if (next !== null && typeof next === "object") {
+    const before = (prev ?? {}) as Fields;
+    return Object.fromEntries(
+      Object.entries(next as Fields).map(([field, value]) => [field, merge(before[field], value, cutoffMs, field)]),
+    );
+  }
+  return next;
+}
+
+// Applies an incremental snapshot (histories holding only points newer than
+// `prev.ts_ms`) on top of the merged one, trimming every series to the window.
+export function mergeSnapshot(prev: MetricsSnapshot, next: MetricsSnapshot): MetricsSnapshot {
+  if (next.ts_ms <= prev.ts_ms) {
+    return prev;
+  }
+  const cutoffMs = next.ts_ms - (next.live_window_ms || HISTORY_WINDOW_MS);
+  const merged = merge(prev, { ...next, gaps: [] }, cutoffMs) as MetricsSnapshot;
+  return { ...merged, gaps: appendGaps(prev.gaps, next.gaps, cutoffMs) };
+}
@@ -1,9 +1,54 @@
 import { invoke } from "@tauri-apps/api/core";
-import type { MetricsSnapshot, ProcessQuery, ProcessRow, ProcessSignal } from "@/types/system";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+import type {
+  ExportRequest,
+  ExportSummary,
+  FilesystemUsage,
+  HistoryRange,
+  HistorySeries,
+  InterruptRates,
+  MetricsSnapshot,
+  ProcessQuery,
+  ProcessRow,
+  ProcessSignal,
+  Resolution,
+  RetentionConfig,
+  SignalInfo,
+  SignalPolicyStatus,
+  TerminateEvent,
+} from "@/types/system";
+
+export const METRICS_EVENT = "metrics://snapshot";
+export const TERMINATE_EVENT = "processes://terminate";
 
 export const tauriApi = {
-  getMetricsSnapshot: () => invoke<MetricsSnapshot>("get_metrics_snapshot"),
+  getMetricsSnapshot: (sinceMs?: number) => invoke<MetricsSnapshot>("get_metrics_snapshot", { sinceMs }),
+  bootstrapMetrics: () => invoke<MetricsSnapshot>("bootstrap_metrics"),
+  startMetricsSampler: (intervalMs?: number) => invoke<number>("start_metrics_sampler", { intervalMs }),
+  stopMetricsSampler: () => invoke<void>("stop_metrics_sampler"),
+  setMetricsInterval: (intervalMs: number) => invoke<number>("set_metrics_interval", { intervalMs }),
+  onMetricsSnapshot: (handler: (snapshot: MetricsSnapshot) => void): Promise<UnlistenFn> =>
+    listen<MetricsSnapshot>(METRICS_EVENT, (event) => handler(event.payload)),
+  queryHistory: (series: HistorySeries, range: { startMs?: number; endMs?: number; resolution?: Resolution } = {}) =>
+    invoke<HistoryRange>("query_history", { series, ...range }),
+  getHistoryRetention: () => invoke<RetentionConfig>("get_history_retention"),
+  setHistoryRetention: (retention: RetentionConfig) =>
+    invoke<RetentionConfig>("set_history_retention", { retention }),
+  listExportSeries: () => invoke<string[]>("list_export_series"),
+  getExportDir: () => invoke<string>("get_export_dir"),
+  exportHistory: (request: ExportRequest) => invoke<ExportSummary>("export_history", { request }),
+  listFilesystems: (includeHistory?: boolean) =>
+    invoke<FilesystemUsage[]>("list_filesystems", { includeHistory }),
+  getInterruptRates: () => invoke<InterruptRates>("get_interrupt_rates"),
   listProcesses: (query: ProcessQuery) => invoke<ProcessRow[]>("list_processes", { query }),
-  sendProcessSignal: (pid: number, signal: ProcessSignal) =>
-    invoke<void>("send_process_signal", { pid, signal }),
+  sendProcessSignal: (pid: number, startTime: number, signal: ProcessSignal) =>
+    invoke<void>("send_process_signal", { pid, startTime, signal }),
+  // Resolves with the grace period used once SIGTERM is sent; the rest arrives
+  // as TerminateEvents.
+  terminateProcess: (pid: number, startTime: number, graceMs?: number) =>
+    invoke<number>("terminate_process", { pid, startTime, graceMs }),
+  onTerminateEvent: (handler: (event: TerminateEvent) => void): Promise<UnlistenFn> =>
+    listen<TerminateEvent>(TERMINATE_EVENT, (event) => handler(event.payload)),
+  listSignals: () => invoke<SignalInfo[]>("list_signals"),
+  getSignalPolicy: () => invoke<SignalPolicyStatus>("get_signal_policy"),
 };
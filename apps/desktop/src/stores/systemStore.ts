@@ -1,6 +1,7 @@
 import { create } from "zustand";
+import { HISTORY_WINDOW_MS, mergeSnapshot } from "@/lib/history";
 import { normalizeIntervalMs, type PollIntervalMs } from "@/lib/polling";
-import type { MetricsSnapshot, ProcessRow } from "@/types/system";
+import type { MetricsSnapshot, PressureStats, ProcessRow } from "@/types/system";
 import { tauriApi } from "@/lib/tauriApi";
 
 export type SystemState = {
@@ -8,14 +9,35 @@ export type SystemState = {
   setIntervalMs: (v: number) => void;
   metrics: MetricsSnapshot;
   processes: ProcessRow[];
-  refreshMetrics: () => Promise<void>;
+  bootstrapMetrics: () => Promise<void>;
+  applySnapshot: (next: MetricsSnapshot) => void;
   refreshProcesses: () => Promise<void>;
 };
 
-export const useSystemStore = create<SystemState>((set) => ({
+const emptyPressure = (): PressureStats => ({
+  some_avg10: 0,
+  some_avg60: 0,
+  some_avg300: 0,
+  some_stall_percent: 0,
+  full_avg10: 0,
+  full_avg60: 0,
+  full_avg300: 0,
+  full_stall_percent: 0,
+  some_history: [],
+  full_history: [],
+});
+
+export const useSystemStore = create<SystemState>((set, get) => ({
   intervalMs: 1000,
-  setIntervalMs: (v) => set({ intervalMs: normalizeIntervalMs(v) }),
+  setIntervalMs: (v) => {
+    const intervalMs = normalizeIntervalMs(v);
+    set({ intervalMs });
+    // Fails with "not running" before the sampler starts; it picks up
+    // intervalMs when it does.
+    void tauriApi.setMetricsInterval(intervalMs).catch(() => undefined);
+  },
   metrics: {
+    ts_ms: 0,
     cpu_percent: 0,
     ram_used_bytes: 0,
     ram_total_bytes: 0,
@@ -31,12 +53,121 @@ export const useSystemStore = create<SystemState>((set) => ({
     disk_write_history: [],
     net_rx_history: [],
     net_tx_history: [],
+    live_window_ms: HISTORY_WINDOW_MS,
+    cpu_modes: {
+      user: 0,
+      nice: 0,
+      system: 0,
+      idle: 0,
+      iowait: 0,
+      irq: 0,
+      softirq: 0,
+      steal: 0,
+      guest: 0,
+      guest_nice: 0,
+    },
+    cpu_mode_history: {
+      user: [],
+      nice: [],
+      system: [],
+      idle: [],
+      iowait: [],
+      irq: [],
+      softirq: [],
+      steal: [],
+      guest: [],
+      guest_nice: [],
+    },
+    cores: [],
+    net_interfaces: [],
+    disks: [],
+    load: {
+      load1: 0,
+      load5: 0,
+      load15: 0,
+      runnable_tasks: 0,
+      total_tasks: 0,
+      procs_running: 0,
+      procs_blocked: 0,
+      forks_per_sec: 0,
+      ctxt_per_sec: 0,
+      intr_per_sec: 0,
+      softirq_per_sec: 0,
+      load1_history: [],
+      load5_history: [],
+      load15_history: [],
+      procs_running_history: [],
+      procs_blocked_history: [],
+      forks_history: [],
+      ctxt_history: [],
+      intr_history: [],
+      softirq_history: [],
+    },
+    pressure: {
+      supported: false,
+      cpu: emptyPressure(),
+      memory: emptyPressure(),
+      io: emptyPressure(),
+    },
+    sensors: [],
+    power: { ac_online: null, batteries: [] },
+    memory: {
+      total_bytes: 0,
+      free_bytes: 0,
+      available_bytes: 0,
+      anon_bytes: 0,
+      cached_bytes: 0,
+      shmem_bytes: 0,
+      buffers_bytes: 0,
+      slab_reclaimable_bytes: 0,
+      slab_unreclaimable_bytes: 0,
+      kernel_stack_bytes: 0,
+      page_tables_bytes: 0,
+      hugetlb_bytes: 0,
+      other_bytes: 0,
+      mapped_bytes: 0,
+      dirty_bytes: 0,
+      writeback_bytes: 0,
+      committed_as_bytes: 0,
+      commit_limit_bytes: 0,
+      hugepages_total: 0,
+      hugepages_free: 0,
+      hugepage_size_bytes: 0,
+      zswap_bytes: 0,
+      zswapped_bytes: 0,
+    },
+    vm: {
+      pgfault_ps: 0,
+      pgmajfault_ps: 0,
+      pswpin_ps: 0,
+      pswpout_ps: 0,
+      pgscan_ps: 0,
+      pgsteal_ps: 0,
+      compact_stall_ps: 0,
+      oom_kill_total: 0,
+      oom_kills: 0,
+      pgfault_history: [],
+      pgmajfault_history: [],
+      pswpin_history: [],
+      pswpout_history: [],
+      pgscan_history: [],
+      pgsteal_history: [],
+      compact_stall_history: [],
+      oom_kill_history: [],
+    },
+    gaps: [],
   },
   processes: [],
-  refreshMetrics: async () => {
-    const metrics = await tauriApi.getMetricsSnapshot();
+  bootstrapMetrics: async () => {
+    const metrics = await tauriApi.bootstrapMetrics();
     set({ metrics });
   },
+  applySnapshot: (next) => {
+    // Sampler events only carry new points, so they need the bootstrap
+    // windows underneath; bootstrapMetrics already covers anything earlier.
+    if (get().metrics.ts_ms === 0) return;
+    set((state) => ({ metrics: mergeSnapshot(state.metrics, next) }));
+  },
   refreshProcesses: async () => {
     const processes = await tauriApi.listProcesses({});
     set({ processes });
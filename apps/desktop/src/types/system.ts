@@ -1,8 +1,12 @@
 export type ProcessRow = {
   pid: number;
+  // Clock ticks since boot; with pid it names the process for signalling.
+  start_time: number;
   user: string;
   command: string;
+  // Share of the whole machine; cpu_core_percent is top-style (100 per core).
   cpu_percent: number;
+  cpu_core_percent: number;
   mem_percent: number;
 };
 
@@ -11,7 +15,243 @@ export type MetricPoint = {
   value: number;
 };
 
+export type CpuModes<T> = {
+  user: T;
+  nice: T;
+  system: T;
+  idle: T;
+  iowait: T;
+  irq: T;
+  softirq: T;
+  steal: T;
+  guest: T;
+  guest_nice: T;
+};
+
+export type CoreFrequency = {
+  cur_khz: number;
+  min_khz: number;
+  max_khz: number;
+  governor: string;
+  energy_performance_preference: string | null;
+  history: MetricPoint[];
+};
+
+export type CoreUsage = {
+  cpu: number;
+  percent: number;
+  history: MetricPoint[];
+  modes: CpuModes<number>;
+  mode_history: CpuModes<MetricPoint[]>;
+  frequency: CoreFrequency | null;
+};
+
+export type NetInterfaceUsage = {
+  name: string;
+  rx_bps: number;
+  tx_bps: number;
+  rx_pps: number;
+  tx_pps: number;
+  rx_errors_ps: number;
+  tx_errors_ps: number;
+  rx_drops_ps: number;
+  tx_drops_ps: number;
+  rx_fifo_ps: number;
+  tx_fifo_ps: number;
+  rx_history: MetricPoint[];
+  tx_history: MetricPoint[];
+};
+
+export type BlockDeviceKind = "disk" | "partition" | "device_mapper" | "virtual" | "optical";
+
+export type DiskUsage = {
+  name: string;
+  kind: BlockDeviceKind;
+  label: string | null;
+  parent: string | null;
+  read_bps: number;
+  write_bps: number;
+  read_iops: number;
+  write_iops: number;
+  busy_percent: number;
+  avg_await_ms: number;
+  in_flight: number;
+  read_history: MetricPoint[];
+  write_history: MetricPoint[];
+  busy_history: MetricPoint[];
+};
+
+export type LoadUsage = {
+  load1: number;
+  load5: number;
+  load15: number;
+  runnable_tasks: number;
+  total_tasks: number;
+  procs_running: number;
+  procs_blocked: number;
+  forks_per_sec: number;
+  ctxt_per_sec: number;
+  intr_per_sec: number;
+  softirq_per_sec: number;
+  load1_history: MetricPoint[];
+  load5_history: MetricPoint[];
+  load15_history: MetricPoint[];
+  procs_running_history: MetricPoint[];
+  procs_blocked_history: MetricPoint[];
+  forks_history: MetricPoint[];
+  ctxt_history: MetricPoint[];
+  intr_history: MetricPoint[];
+  softirq_history: MetricPoint[];
+};
+
+export type PressureStats = {
+  some_avg10: number;
+  some_avg60: number;
+  some_avg300: number;
+  some_stall_percent: number;
+  full_avg10: number;
+  full_avg60: number;
+  full_avg300: number;
+  full_stall_percent: number;
+  some_history: MetricPoint[];
+  full_history: MetricPoint[];
+};
+
+export type PressureUsage = {
+  supported: boolean;
+  cpu: PressureStats;
+  memory: PressureStats;
+  io: PressureStats;
+};
+
+export type SensorKind = "temperature" | "fan";
+
+export type SensorUsage = {
+  id: string;
+  chip: string;
+  label: string;
+  kind: SensorKind;
+  value: number;
+  max: number | null;
+  crit: number | null;
+  history: MetricPoint[];
+};
+
+export type BatteryUsage = {
+  name: string;
+  status: string;
+  capacity_percent: number | null;
+  energy_now_wh: number | null;
+  energy_full_wh: number | null;
+  energy_full_design_wh: number | null;
+  wear_percent: number | null;
+  power_now_w: number | null;
+  time_to_empty_secs: number | null;
+  discharge_history: MetricPoint[];
+};
+
+export type PowerUsage = {
+  ac_online: boolean | null;
+  batteries: BatteryUsage[];
+};
+
+export type MemoryBreakdown = {
+  total_bytes: number;
+  free_bytes: number;
+  available_bytes: number;
+  anon_bytes: number;
+  cached_bytes: number;
+  shmem_bytes: number;
+  buffers_bytes: number;
+  slab_reclaimable_bytes: number;
+  slab_unreclaimable_bytes: number;
+  kernel_stack_bytes: number;
+  page_tables_bytes: number;
+  hugetlb_bytes: number;
+  other_bytes: number;
+  mapped_bytes: number;
+  dirty_bytes: number;
+  writeback_bytes: number;
+  committed_as_bytes: number;
+  commit_limit_bytes: number;
+  hugepages_total: number;
+  hugepages_free: number;
+  hugepage_size_bytes: number;
+  zswap_bytes: number;
+  zswapped_bytes: number;
+};
+
+export type VmActivity = {
+  pgfault_ps: number;
+  pgmajfault_ps: number;
+  pswpin_ps: number;
+  pswpout_ps: number;
+  pgscan_ps: number;
+  pgsteal_ps: number;
+  compact_stall_ps: number;
+  oom_kill_total: number;
+  oom_kills: number;
+  pgfault_history: MetricPoint[];
+  pgmajfault_history: MetricPoint[];
+  pswpin_history: MetricPoint[];
+  pswpout_history: MetricPoint[];
+  pgscan_history: MetricPoint[];
+  pgsteal_history: MetricPoint[];
+  compact_stall_history: MetricPoint[];
+  oom_kill_history: MetricPoint[];
+};
+
+export type HistorySeries = "cpu" | "ram" | "disk_read" | "disk_write" | "net_rx" | "net_tx";
+
+export type Resolution = "auto" | "raw" | "ten_seconds" | "one_minute";
+
+export type RollupPoint = {
+  ts_ms: number;
+  min: number;
+  avg: number;
+  max: number;
+};
+
+export type HistoryRange = {
+  series: HistorySeries;
+  resolution: Resolution;
+  points: RollupPoint[];
+};
+
+export type ExportFormat = "csv" | "jsonl" | "influx";
+
+export type ExportRequest = {
+  // Absolute path inside the directory returned by getExportDir.
+  path: string;
+  format: ExportFormat;
+  // Aggregate names ("cpu", "net_rx", ...) or device series from
+  // listExportSeries. Device series only cover the last live_window_ms.
+  series: string[];
+  start_ms?: number;
+  end_ms?: number;
+  resolution?: Resolution;
+};
+
+export type ExportSummary = {
+  path: string;
+  series: number;
+  rows: number;
+};
+
+// Clamped by the backend to 1..=1440 minutes, 1..=168 hours and 1..=90 days.
+export type RetentionConfig = {
+  raw_minutes: number;
+  ten_second_hours: number;
+  one_minute_days: number;
+};
+
+export type HistoryGap = {
+  start_ms: number;
+  end_ms: number;
+};
+
 export type MetricsSnapshot = {
+  ts_ms: number;
   cpu_percent: number;
   ram_used_bytes: number;
   ram_total_bytes: number;
@@ -27,6 +267,57 @@ export type MetricsSnapshot = {
   disk_write_history: MetricPoint[];
   net_rx_history: MetricPoint[];
   net_tx_history: MetricPoint[];
+  // Every *_history in a snapshot reaches back at most this far. Only the six
+  // aggregates have longer history (queryHistory); per-core, per-device,
+  // load, pressure, vmstat, sensor and battery series are live only.
+  live_window_ms: number;
+  cpu_modes: CpuModes<number>;
+  cpu_mode_history: CpuModes<MetricPoint[]>;
+  cores: CoreUsage[];
+  net_interfaces: NetInterfaceUsage[];
+  disks: DiskUsage[];
+  load: LoadUsage;
+  pressure: PressureUsage;
+  sensors: SensorUsage[];
+  power: PowerUsage;
+  memory: MemoryBreakdown;
+  vm: VmActivity;
+  gaps: HistoryGap[];
+};
+
+export type FilesystemUsage = {
+  mount_point: string;
+  source: string;
+  fs_type: string;
+  total_bytes: number;
+  used_bytes: number;
+  free_bytes: number;
+  available_bytes: number;
+  used_percent: number;
+  inodes_total: number;
+  inodes_used: number;
+  inodes_free: number;
+  inodes_used_percent: number;
+  history: MetricPoint[] | null;
+};
+
+export type IrqRate = {
+  source: string;
+  label: string;
+  // Empty for system-wide rows such as ERR and MIS.
+  per_cpu: number[];
+  total_per_sec: number;
+};
+
+export type IrqRateTable = {
+  cpus: number[];
+  rows: IrqRate[];
+};
+
+export type InterruptRates = {
+  interval_ms: number;
+  interrupts: IrqRateTable;
+  softirqs: IrqRateTable;
 };
 
 export type ProcessQuery = {
@@ -37,4 +328,55 @@ export type ProcessQuery = {
   offset?: number;
 };
 
-export type ProcessSignal = "Sigterm" | "Sigkill" | "Sigstop" | "Sigcont";
+// A signal number or a name such as "SIGHUP", "hup" or "SIGRTMIN+3".
+export type ProcessSignal = string | number;
+
+export type TerminateStage =
+  | "term_sent"
+  | "waiting"
+  | "kill_sent"
+  | "exited"
+  | "killed"
+  | "still_running"
+  | "failed";
+
+// Emitted on TERMINATE_EVENT; exited, killed, still_running and failed are final.
+export type TerminateEvent = {
+  pid: number;
+  start_time: number;
+  stage: TerminateStage;
+  elapsed_ms: number;
+  grace_ms: number;
+  // proc(5) state letter when the process outlived SIGKILL, e.g. "D".
+  process_state: string | null;
+  error: string | null;
+};
+
+export type SignalAction = "terminate" | "core_dump" | "ignore" | "stop" | "continue";
+
+export type SignalInfo = {
+  number: number;
+  name: string;
+  description: string;
+  default_action: SignalAction;
+  allowed: boolean;
+};
+
+// An empty allow list admits every signal that is not denied.
+export type SignalPolicy = {
+  allow: string[];
+  deny: string[];
+};
+
+// `path` is the policy file read at startup, whether or not it exists;
+// `error` says why the built-in default was used instead of it.
+export type PolicySource = {
+  path: string | null;
+  from_file: boolean;
+  error: string | null;
+};
+
+export type SignalPolicyStatus = {
+  policy: SignalPolicy;
+  source: PolicySource;
+};